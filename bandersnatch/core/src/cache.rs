@@ -0,0 +1,304 @@
+//! Ring data cache.
+//!
+//! Within an epoch the ring stays the same, yet every commitment, proof
+//! generation and verification call would otherwise rebuild the verifier key,
+//! prover key or verifier from scratch. This module keeps a small LRU of the
+//! derived data, keyed by a hash of the ring keys (or by the commitment, when
+//! only the commitment is known, as during verification).
+
+use std::sync::{Arc, Mutex};
+
+use ark_vrf::reexports::ark_serialize::CanonicalSerialize;
+use sha2::{Digest, Sha256};
+
+use crate::bandersnatch::{Public, RingProverKey, RingVerifier, RingVerifierKey};
+use crate::{Error, RingCommitment, RingSize, ring_proof_params};
+
+/// Maximal number of rings kept in the cache.
+///
+/// Enough for the current and next epoch's rings (plus a couple of spare ones
+/// during forks), while bounding memory usage for the full ring.
+pub const RING_CACHE_CAPACITY: usize = 4;
+
+/// Hash identifying a ring of keys of given size.
+pub(crate) type RingHash = [u8; 32];
+
+#[derive(Default)]
+pub(crate) struct RingCacheEntry {
+    ring_size: Option<RingSize>,
+    /// Hash of the ring keys (unknown for entries created from a commitment).
+    keys_hash: Option<RingHash>,
+    /// Serialized ring commitment.
+    commitment: Option<Vec<u8>>,
+    prover_key: Option<Arc<RingProverKey>>,
+    verifier_key: Option<RingVerifierKey>,
+    verifier: Option<Arc<RingVerifier>>,
+}
+
+/// Least-recently-used collection of ring data.
+///
+/// The most recently used entry is kept at the end.
+pub(crate) struct RingCache {
+    capacity: usize,
+    pub(crate) entries: Vec<RingCacheEntry>,
+    /// Number of ring verifier keys built on cache miss.
+    #[cfg(test)]
+    pub(crate) verifier_key_builds: usize,
+}
+
+impl RingCache {
+    pub(crate) const fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::new(),
+            #[cfg(test)]
+            verifier_key_builds: 0,
+        }
+    }
+
+    fn find(&mut self, matches: impl Fn(&RingCacheEntry) -> bool) -> Option<&mut RingCacheEntry> {
+        let index = self.entries.iter().position(matches)?;
+        let entry = self.entries.remove(index);
+        self.entries.push(entry);
+        self.entries.last_mut()
+    }
+
+    pub(crate) fn by_keys(
+        &mut self,
+        ring_size: RingSize,
+        keys_hash: &RingHash,
+    ) -> Option<&mut RingCacheEntry> {
+        self.find(|e| e.ring_size == Some(ring_size) && e.keys_hash.as_ref() == Some(keys_hash))
+    }
+
+    pub(crate) fn by_commitment(
+        &mut self,
+        ring_size: RingSize,
+        commitment: &[u8],
+    ) -> Option<&mut RingCacheEntry> {
+        self.find(|e| e.ring_size == Some(ring_size) && e.commitment.as_deref() == Some(commitment))
+    }
+
+    /// Get an entry for the ring, creating (and possibly evicting the oldest one) if missing.
+    pub(crate) fn entry(
+        &mut self,
+        ring_size: RingSize,
+        keys_hash: Option<RingHash>,
+        commitment: Option<&[u8]>,
+    ) -> &mut RingCacheEntry {
+        let index = self.entries.iter().position(|e| {
+            e.ring_size == Some(ring_size)
+                && ((keys_hash.is_some() && e.keys_hash == keys_hash)
+                    || (commitment.is_some() && e.commitment.as_deref() == commitment))
+        });
+
+        let mut entry = match index {
+            Some(index) => self.entries.remove(index),
+            None => {
+                if self.entries.len() >= self.capacity {
+                    self.entries.remove(0);
+                }
+                RingCacheEntry {
+                    ring_size: Some(ring_size),
+                    ..Default::default()
+                }
+            }
+        };
+
+        if entry.keys_hash.is_none() {
+            entry.keys_hash = keys_hash;
+        }
+        if entry.commitment.is_none() {
+            entry.commitment = commitment.map(|c| c.to_vec());
+        }
+
+        self.entries.push(entry);
+        self.entries.last_mut().expect("entry was just pushed")
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Ring cache shared between threads.
+pub(crate) struct SharedRingCache(Mutex<RingCache>);
+
+/// Cache used by all ring operations.
+pub(crate) static RING_CACHE: SharedRingCache = SharedRingCache::new(RING_CACHE_CAPACITY);
+
+/// Drop all cached ring data.
+///
+/// Should be called when the cached rings are known not to be used anymore
+/// (e.g. after an epoch change), to release the memory early.
+pub fn clear_ring_cache() {
+    RING_CACHE.with(RingCache::clear);
+}
+
+fn ring_keys_hash(keys: &[Public], ring_size: RingSize) -> RingHash {
+    let mut hasher = Sha256::new();
+    hasher.update((ring_size.size() as u32).to_le_bytes());
+    let mut buf = Vec::with_capacity(crate::PUBLIC_KEY_SIZE);
+    for key in keys {
+        buf.clear();
+        key.serialize_compressed(&mut buf)
+            .expect("serialization into a vector can't fail");
+        hasher.update(&buf);
+    }
+    hasher.finalize().into()
+}
+
+fn serialize_commitment(commitment: &RingCommitment) -> Result<Vec<u8>, Error> {
+    let mut commitment_bytes = Vec::new();
+    commitment
+        .serialize_compressed(&mut commitment_bytes)
+        .map_err(|_| Error::InvalidSignature)?;
+    Ok(commitment_bytes)
+}
+
+impl SharedRingCache {
+    pub(crate) const fn new(capacity: usize) -> Self {
+        Self(Mutex::new(RingCache::new(capacity)))
+    }
+
+    /// Run `f` with the cache locked.
+    ///
+    /// A poisoned lock only means another thread panicked mid-update, and the
+    /// cache content is always consistent, so we just keep using it.
+    pub(crate) fn with<R>(&self, f: impl FnOnce(&mut RingCache) -> R) -> R {
+        let mut cache = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut cache)
+    }
+
+    /// Serialized ring commitment for given keys, computed only on cache miss.
+    pub(crate) fn ring_commitment(
+        &self,
+        keys: &[Public],
+        ring_size: RingSize,
+    ) -> Result<Vec<u8>, Error> {
+        let keys_hash = ring_keys_hash(keys, ring_size);
+        let cached = self.with(|cache| {
+            cache
+                .by_keys(ring_size, &keys_hash)
+                .and_then(|e| e.commitment.clone())
+        });
+        if let Some(commitment) = cached {
+            return Ok(commitment);
+        }
+
+        let pts: Vec<_> = keys.iter().map(|pk| pk.0).collect();
+        let verifier_key = ring_proof_params(ring_size)?.verifier_key(&pts);
+        let mut commitment = Vec::new();
+        verifier_key
+            .commitment()
+            .serialize_compressed(&mut commitment)
+            .map_err(|_| Error::InvalidSignature)?;
+
+        self.with(|cache| {
+            cache.entry(ring_size, Some(keys_hash), Some(&commitment));
+        });
+        Ok(commitment)
+    }
+
+    /// Ring prover key for given keys, computed only on cache miss.
+    pub(crate) fn ring_prover_key(
+        &self,
+        keys: &[Public],
+        ring_size: RingSize,
+    ) -> Result<Arc<RingProverKey>, Error> {
+        let keys_hash = ring_keys_hash(keys, ring_size);
+        let cached = self.with(|cache| {
+            cache
+                .by_keys(ring_size, &keys_hash)
+                .and_then(|e| e.prover_key.clone())
+        });
+        if let Some(prover_key) = cached {
+            return Ok(prover_key);
+        }
+
+        let pts: Vec<_> = keys.iter().map(|pk| pk.0).collect();
+        let prover_key = Arc::new(ring_proof_params(ring_size)?.prover_key(&pts));
+
+        self.with(|cache| {
+            cache.entry(ring_size, Some(keys_hash), None).prover_key = Some(prover_key.clone());
+        });
+        Ok(prover_key)
+    }
+
+    /// Ring verifier key for given commitment, built only on cache miss.
+    pub(crate) fn ring_verifier_key(
+        &self,
+        ring_size: RingSize,
+        commitment: RingCommitment,
+    ) -> Result<RingVerifierKey, Error> {
+        let commitment_bytes = serialize_commitment(&commitment)?;
+
+        let cached = self.with(|cache| {
+            cache
+                .by_commitment(ring_size, &commitment_bytes)
+                .and_then(|e| e.verifier_key.clone())
+        });
+        if let Some(verifier_key) = cached {
+            return Ok(verifier_key);
+        }
+
+        let verifier_key = ring_proof_params(ring_size)?.verifier_key_from_commitment(commitment);
+
+        self.with(|cache| {
+            #[cfg(test)]
+            {
+                cache.verifier_key_builds += 1;
+            }
+            cache
+                .entry(ring_size, None, Some(&commitment_bytes))
+                .verifier_key = Some(verifier_key.clone());
+        });
+        Ok(verifier_key)
+    }
+
+    /// Ring verifier for given commitment, built only on cache miss.
+    pub(crate) fn ring_verifier(
+        &self,
+        ring_size: RingSize,
+        commitment: RingCommitment,
+    ) -> Result<Arc<RingVerifier>, Error> {
+        let commitment_bytes = serialize_commitment(&commitment)?;
+
+        let cached = self.with(|cache| {
+            cache
+                .by_commitment(ring_size, &commitment_bytes)
+                .and_then(|e| e.verifier.clone())
+        });
+        if let Some(verifier) = cached {
+            return Ok(verifier);
+        }
+
+        let verifier_key = self.ring_verifier_key(ring_size, commitment)?;
+        let verifier = Arc::new(crate::build_ring_verifier(ring_size, verifier_key)?);
+
+        self.with(|cache| {
+            cache
+                .entry(ring_size, None, Some(&commitment_bytes))
+                .verifier = Some(verifier.clone());
+        });
+        Ok(verifier)
+    }
+
+    /// Store a ring verifier key (e.g. loaded from a snapshot), together with
+    /// the verifier built from it.
+    pub(crate) fn insert_ring_verifier_key(
+        &self,
+        ring_size: RingSize,
+        verifier_key: RingVerifierKey,
+    ) -> Result<(), Error> {
+        let commitment_bytes = serialize_commitment(&verifier_key.commitment())?;
+        let verifier = Arc::new(crate::build_ring_verifier(ring_size, verifier_key.clone())?);
+
+        self.with(|cache| {
+            let entry = cache.entry(ring_size, None, Some(&commitment_bytes));
+            entry.verifier_key = Some(verifier_key);
+            entry.verifier = Some(verifier);
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+//! Batched IETF VRF verification.
+//!
+//! IETF proofs are in the `(c, s)` form, so unlike `(R, s)` Schnorr proofs
+//! they can't be folded into one random linear combination: each challenge
+//! has to be recomputed from its own nonce commitments `u = s*G - c*Y` and
+//! `v = s*H - c*O`. What a batch can share is computing every commitment as a
+//! two-term multi-scalar multiplication, and normalizing all of them to affine
+//! with a single field inversion.
+//!
+//! This also means a failed batch needs no fallback pass to find the invalid
+//! proofs: every proof gets its own result.
+
+use ark_vrf::Suite;
+use ark_vrf::reexports::ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_vrf::reexports::ark_serialize::CanonicalDeserialize;
+
+use crate::bandersnatch::{BandersnatchSha512Ell2, IetfProof, Input, Output, Public};
+use crate::{Error, IetfVrfSignature, vrf_input_point};
+
+type Affine = <BandersnatchSha512Ell2 as Suite>::Affine;
+type Projective = <Affine as AffineRepr>::Group;
+
+/// One IETF VRF proof to verify.
+pub(crate) struct IetfCheck<'a> {
+    pub(crate) public: Public,
+    pub(crate) input: Input,
+    pub(crate) output: Output,
+    pub(crate) aux_data: &'a [u8],
+    pub(crate) proof: IetfProof,
+}
+
+impl<'a> IetfCheck<'a> {
+    /// Check of `signature` over `vrf_input_data` and `aux_data`.
+    pub(crate) fn new(
+        public: Public,
+        vrf_input_data: &[u8],
+        aux_data: &'a [u8],
+        signature: &[u8],
+    ) -> Result<Self, Error> {
+        let signature = IetfVrfSignature::deserialize_compressed_unchecked(signature)
+            .map_err(|_| Error::InvalidSignature)?;
+        Ok(Self {
+            public,
+            input: vrf_input_point(vrf_input_data)?,
+            output: signature.output,
+            aux_data,
+            proof: signature.proof,
+        })
+    }
+}
+
+/// Nonce commitments `u` and `v` of a proof, in projective form.
+fn nonce_commitments(check: &IetfCheck) -> [Projective; 2] {
+    let IetfProof { c, s } = &check.proof;
+    let scalars = [*s, -*c];
+    let u = Projective::msm_unchecked(
+        &[BandersnatchSha512Ell2::generator(), check.public.0],
+        &scalars,
+    );
+    let v = Projective::msm_unchecked(&[check.input.0, check.output.0], &scalars);
+    [u, v]
+}
+
+/// Verify IETF VRF proofs, returning whether each of them is valid.
+///
+/// Gives the same results as verifying each proof on its own.
+pub(crate) fn verify_ietf_batch(checks: &[IetfCheck]) -> Vec<bool> {
+    #[cfg(feature = "parallel")]
+    let commitments: Vec<_> = {
+        use rayon::prelude::*;
+        checks.par_iter().flat_map_iter(nonce_commitments).collect()
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    let commitments: Vec<_> = checks.iter().flat_map(nonce_commitments).collect();
+
+    let commitments = Projective::normalize_batch(&commitments);
+
+    let verify = |(check, uv): (&IetfCheck, &[Affine])| {
+        let challenge = BandersnatchSha512Ell2::challenge(
+            &[
+                &check.public.0,
+                &check.input.0,
+                &check.output.0,
+                &uv[0],
+                &uv[1],
+            ],
+            check.aux_data,
+        );
+        challenge == check.proof.c
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        checks
+            .par_iter()
+            .zip(commitments.par_chunks_exact(2))
+            .map(verify)
+            .collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        checks
+            .iter()
+            .zip(commitments.chunks_exact(2))
+            .map(verify)
+            .collect()
+    }
+}
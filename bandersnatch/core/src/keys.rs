@@ -0,0 +1,129 @@
+//! Secret key handles.
+//!
+//! Signing functions taking a `secret_seed` re-derive the secret scalar on
+//! every call. Instead, a seed (or a raw secret scalar) can be imported once
+//! into an opaque [`KeyHandle`], which is then used for signing. The secrets
+//! stay in Rust memory until released with [`release_secret_key`].
+//!
+//! Keys can also be moved in and out of a passphrase-encrypted keystore file
+//! (see the `keystore` crate), so that seeds never have to leave Rust.
+
+use std::sync::{Arc, LazyLock, Mutex};
+
+use ark_vrf::reexports::ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use keystore::{KdfParams, KeyRegistry, KeyType, Keystore};
+use zeroize::Zeroizing;
+
+use crate::Error;
+use crate::bandersnatch::{ScalarField, Secret};
+
+/// Opaque handle of an imported secret key.
+pub type KeyHandle = keystore::KeyId;
+
+/// Size of a serialized secret scalar in bytes.
+pub const SECRET_SCALAR_SIZE: usize = 32;
+
+static SECRET_KEYS: LazyLock<Mutex<KeyRegistry<Secret>>> =
+    LazyLock::new(|| Mutex::new(KeyRegistry::new()));
+
+/// Run `f` with the key registry locked.
+///
+/// Like the ring cache, the registry is consistent even if the lock was poisoned.
+fn with_registry<R>(f: impl FnOnce(&mut KeyRegistry<Secret>) -> R) -> R {
+    let mut registry = SECRET_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut registry)
+}
+
+fn insert_secret_key(secret: Secret) -> KeyHandle {
+    with_registry(|registry| registry.insert(secret))
+}
+
+/// Get the secret key of a handle.
+pub(crate) fn secret_key(handle: KeyHandle) -> Result<Arc<Secret>, Error> {
+    with_registry(|registry| registry.get(handle)).ok_or(Error::UnknownKeyHandle)
+}
+
+/// Derive the secret key from a seed and import it.
+pub fn import_secret_seed(seed: &[u8]) -> KeyHandle {
+    insert_secret_key(Secret::from_seed(seed))
+}
+
+/// Import a raw secret scalar (32 bytes, little endian).
+///
+/// Fails for non-canonical encodings and for the zero scalar.
+pub fn import_secret_scalar(scalar: &[u8]) -> Result<KeyHandle, Error> {
+    Ok(insert_secret_key(parse_secret_scalar(scalar)?))
+}
+
+fn parse_secret_scalar(scalar: &[u8]) -> Result<Secret, Error> {
+    use ark_vrf::reexports::ark_ff::Zero;
+
+    if scalar.len() != SECRET_SCALAR_SIZE {
+        return Err(Error::InvalidSecretKey);
+    }
+    let scalar =
+        ScalarField::deserialize_compressed(scalar).map_err(|_| Error::InvalidSecretKey)?;
+    if scalar.is_zero() {
+        return Err(Error::InvalidSecretKey);
+    }
+    Ok(Secret::from_scalar(scalar))
+}
+
+/// Export the raw secret scalar of a key. The returned copy is wiped on drop.
+pub fn export_secret_scalar(
+    handle: KeyHandle,
+) -> Result<Zeroizing<[u8; SECRET_SCALAR_SIZE]>, Error> {
+    let secret = secret_key(handle)?;
+    let mut scalar = Zeroizing::new([0u8; SECRET_SCALAR_SIZE]);
+    secret
+        .scalar()
+        .serialize_compressed(&mut scalar[..])
+        .map_err(|_| Error::InvalidSecretKey)?;
+    Ok(scalar)
+}
+
+/// Public key of an imported secret key.
+pub fn secret_key_public(handle: KeyHandle) -> Result<Vec<u8>, Error> {
+    let secret = secret_key(handle)?;
+    let mut buf = Vec::new();
+    secret
+        .public()
+        .serialize_compressed(&mut buf)
+        .map_err(|_| Error::InvalidSecretKey)?;
+    Ok(buf)
+}
+
+/// Forget a secret key. Returns `false` if the handle was unknown.
+pub fn release_secret_key(handle: KeyHandle) -> bool {
+    with_registry(|registry| registry.remove(handle))
+}
+
+/// Import the bandersnatch keys of a keystore file.
+///
+/// Keys of other types are ignored. Nothing is imported if any key is invalid.
+pub fn import_keystore(file: &[u8], passphrase: &[u8]) -> Result<Vec<KeyHandle>, Error> {
+    let keystore = Keystore::decrypt(file, passphrase).map_err(Error::Keystore)?;
+    let secrets = keystore
+        .secrets(KeyType::Bandersnatch)
+        .map(parse_secret_scalar)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(secrets.into_iter().map(insert_secret_key).collect())
+}
+
+/// Seal the secret scalars of given keys into a keystore file.
+pub fn export_keystore(
+    handles: &[KeyHandle],
+    passphrase: &[u8],
+    params: KdfParams,
+) -> Result<Vec<u8>, Error> {
+    let mut keystore = Keystore::new();
+    for handle in handles {
+        let scalar = export_secret_scalar(*handle)?;
+        keystore
+            .add(KeyType::Bandersnatch, &scalar[..])
+            .map_err(Error::Keystore)?;
+    }
+    keystore
+        .encrypt(passphrase, params)
+        .map_err(Error::Keystore)
+}
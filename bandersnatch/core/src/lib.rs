@@ -2,17 +2,41 @@
 //!
 //! Pure Rust implementation of Bandersnatch VRF functionality without any
 //! binding-specific code. This crate can be used by both WASM and native bindings.
+//!
+//! Secret material is wiped from memory once it's no longer needed: `Secret`s
+//! zeroize their scalar on drop, and copies of seeds or scalars are kept in
+//! [`zeroize::Zeroizing`] buffers. Apart from [`export_secret_scalar`], no
+//! returned buffer or error carries secret data.
 
 use ark_vrf::ietf::Prover;
-use ark_vrf::reexports::ark_serialize::{self, CanonicalDeserialize, CanonicalSerialize};
+use ark_vrf::reexports::ark_serialize::{
+    self, CanonicalDeserialize, CanonicalSerialize, Compress, Validate,
+};
 use ark_vrf::suites::bandersnatch;
 use bandersnatch::{
-    BandersnatchSha512Ell2, IetfProof, Input, Output, Public, RingProof, RingProofParams,
-    RingProver, RingVerifier, Secret,
+    BandersnatchSha512Ell2, IetfProof, Input, Output, PcsParams, PedersenProof, Public, RingProof,
+    RingProofParams, RingProver, RingProverKey, RingVerifier, RingVerifierKey, ScalarField, Secret,
 };
+use std::sync::{Arc, OnceLock};
 
+mod cache;
+mod ietf_batch;
+mod keys;
+mod ring_update;
+mod snapshot;
 #[cfg(test)]
 mod test;
+#[cfg(test)]
+mod test_vectors;
+
+use cache::{RING_CACHE, SharedRingCache};
+pub use cache::{RING_CACHE_CAPACITY, clear_ring_cache};
+pub use keys::{
+    KeyHandle, SECRET_SCALAR_SIZE, export_keystore, export_secret_scalar, import_keystore,
+    import_secret_scalar, import_secret_seed, release_secret_key, secret_key_public,
+};
+pub use keystore::KdfParams;
+pub use snapshot::{load_ring_params, serialize_ring_params};
 
 /// Size of supported rings.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -33,6 +57,9 @@ impl RingSize {
     }
 
     /// Create a RingSize from a numeric size.
+    ///
+    /// Any size other than the full ring size is taken as tiny, see
+    /// [`RingSize::from_key_count`] for a strict conversion.
     pub fn from_size(size: usize) -> Self {
         if size == Self::Full.size() {
             RingSize::Full
@@ -40,6 +67,16 @@ impl RingSize {
             RingSize::Tiny
         }
     }
+
+    /// Ring size of a ring with `count` keys, which must be exactly the tiny
+    /// or the full ring size.
+    pub fn from_key_count(count: usize) -> Result<Self, Error> {
+        match count {
+            c if c == Self::Tiny.size() => Ok(RingSize::Tiny),
+            c if c == Self::Full.size() => Ok(RingSize::Full),
+            _ => Err(Error::UnsupportedRingSize),
+        }
+    }
 }
 
 /// IETF VRF signature as described in section 2.2 of the Bandersnatch VRFs specification.
@@ -49,6 +86,14 @@ pub struct IetfVrfSignature {
     pub proof: IetfProof,
 }
 
+/// Pedersen VRF signature as described in section 3 of the Bandersnatch VRFs specification.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct PedersenVrfSignature {
+    pub output: Output,
+    /// Contains the blinded public key commitment.
+    pub proof: PedersenProof,
+}
+
 /// Ring VRF signature as described in section 4.2 of the Bandersnatch VRFs specification.
 #[derive(CanonicalSerialize, CanonicalDeserialize)]
 pub struct RingVrfSignature {
@@ -57,36 +102,139 @@ pub struct RingVrfSignature {
     pub proof: RingProof,
 }
 
-/// Get or initialize ring proof parameters for the given ring size.
-pub fn ring_proof_params(ring_size: RingSize) -> &'static RingProofParams {
-    use std::sync::OnceLock;
-    static PARAMS_TINY: OnceLock<RingProofParams> = OnceLock::new();
-    static PARAMS_FULL: OnceLock<RingProofParams> = OnceLock::new();
+/// PCS (KZG SRS) parameters used to derive the ring proof parameters.
+static PCS_PARAMS: OnceLock<PcsParams> = OnceLock::new();
 
-    let init = |size: usize| {
-        use bandersnatch::PcsParams;
-        let buf = include_bytes!("../../data/zcash-srs-2-11-uncompressed.bin");
-        let pcs_params = PcsParams::deserialize_uncompressed_unchecked(&mut &buf[..])
-            .expect("binary data invalid");
-        RingProofParams::from_pcs_params(size, pcs_params).expect("invalid ring proof params")
+/// Check that the length prefixes of serialized PCS params match the data length.
+///
+/// The deserializer pre-allocates vectors according to these prefixes, so they
+/// must be checked before parsing untrusted input.
+fn pcs_params_layout_matches(bytes: &[u8], compress: Compress) -> bool {
+    type G1Affine = ark_vrf::ring::G1Affine<BandersnatchSha512Ell2>;
+    type G2Affine = ark_vrf::ring::G2Affine<BandersnatchSha512Ell2>;
+
+    fn read_len(bytes: &[u8]) -> Option<(usize, &[u8])> {
+        let (len, rest) = bytes.split_first_chunk::<8>()?;
+        Some((usize::try_from(u64::from_le_bytes(*len)).ok()?, rest))
+    }
+
+    let g1_size = G1Affine::default().serialized_size(compress);
+    let g2_size = G2Affine::default().serialized_size(compress);
+
+    let Some((g1_count, rest)) = read_len(bytes) else {
+        return false;
     };
+    let Some(rest) = g1_count
+        .checked_mul(g1_size)
+        .and_then(|len| rest.get(len..))
+    else {
+        return false;
+    };
+    let Some((g2_count, rest)) = read_len(rest) else {
+        return false;
+    };
+    g2_count.checked_mul(g2_size) == Some(rest.len())
+}
+
+/// Deserialize and validate PCS parameters.
+///
+/// Both compressed and uncompressed serializations are accepted. The points
+/// are fully checked and the parameters must be large enough for the full ring.
+pub fn parse_pcs_params(bytes: &[u8]) -> Result<PcsParams, Error> {
+    let pcs_params = [Compress::Yes, Compress::No]
+        .into_iter()
+        .find(|&compress| pcs_params_layout_matches(bytes, compress))
+        .and_then(|compress| PcsParams::deserialize_with_mode(bytes, compress, Validate::Yes).ok())
+        .ok_or(Error::InvalidPcsParams)?;
+
+    RingProofParams::from_pcs_params(RingSize::Full.size(), pcs_params.clone())
+        .map_err(|_| Error::InvalidPcsParams)?;
+
+    Ok(pcs_params)
+}
+
+/// Supply the PCS parameters used for ring proofs.
+///
+/// Must be called before any ring operation, since the ring proof parameters
+/// are derived only once. Fails if the parameters are invalid or already set
+/// (including the embedded default being used already).
+pub fn init_pcs_params(bytes: &[u8]) -> Result<(), Error> {
+    if PCS_PARAMS.get().is_some() {
+        return Err(Error::PcsParamsAlreadySet);
+    }
+
+    let pcs_params = parse_pcs_params(bytes)?;
+    PCS_PARAMS
+        .set(pcs_params)
+        .map_err(|_| Error::PcsParamsAlreadySet)
+}
+
+/// Get the PCS parameters, falling back to the embedded ones (if available).
+///
+/// With the `compressed-srs` feature the embedded SRS is stored with compressed
+/// points (half the size), at the cost of decompressing them on first use.
+fn pcs_params() -> Result<&'static PcsParams, Error> {
+    #[cfg(all(feature = "embedded-srs", not(feature = "compressed-srs")))]
+    {
+        Ok(PCS_PARAMS.get_or_init(|| {
+            let buf = include_bytes!("../../data/zcash-srs-2-11-uncompressed.bin");
+            PcsParams::deserialize_uncompressed_unchecked(&mut &buf[..])
+                .expect("binary data invalid")
+        }))
+    }
+
+    #[cfg(feature = "compressed-srs")]
+    {
+        Ok(PCS_PARAMS.get_or_init(|| {
+            let buf = include_bytes!("../../data/zcash-srs-2-11-compressed.bin");
+            PcsParams::deserialize_compressed_unchecked(&mut &buf[..]).expect("binary data invalid")
+        }))
+    }
+
+    #[cfg(not(feature = "embedded-srs"))]
+    {
+        PCS_PARAMS.get().ok_or(Error::MissingPcsParams)
+    }
+}
+
+/// Storage of the ring proof parameters derived for given ring size.
+fn ring_params_slot(ring_size: RingSize) -> &'static OnceLock<RingProofParams> {
+    static PARAMS_TINY: OnceLock<RingProofParams> = OnceLock::new();
+    static PARAMS_FULL: OnceLock<RingProofParams> = OnceLock::new();
 
     match ring_size {
-        RingSize::Tiny => PARAMS_TINY.get_or_init(|| init(ring_size.size())),
-        RingSize::Full => PARAMS_FULL.get_or_init(|| init(ring_size.size())),
+        RingSize::Tiny => &PARAMS_TINY,
+        RingSize::Full => &PARAMS_FULL,
+    }
+}
+
+/// Get or initialize ring proof parameters for the given ring size.
+pub fn ring_proof_params(ring_size: RingSize) -> Result<&'static RingProofParams, Error> {
+    let params = ring_params_slot(ring_size);
+
+    if let Some(params) = params.get() {
+        return Ok(params);
     }
+
+    let pcs_params = pcs_params()?.clone();
+    let ring_params = RingProofParams::from_pcs_params(ring_size.size(), pcs_params)
+        .map_err(|_| Error::InvalidPcsParams)?;
+    // Another thread might have been faster, both results are the same.
+    Ok(params.get_or_init(|| ring_params))
 }
 
-/// Build a ring verifier from a commitment for the given ring size.
+/// Build a ring verifier from a verifier key for the given ring size.
 ///
 /// The verifier key is reconstructed from the commitment and the constant
-/// verifier key component of the SRS. Constructing the verifier clones the full
-/// `PiopParams` (sized to the ring's domain), so callers verifying many
-/// signatures against the same ring should build it once and reuse it.
-fn build_ring_verifier(ring_size: RingSize, commitment: RingCommitment) -> RingVerifier {
-    let ring_params = ring_proof_params(ring_size);
-    let verifier_key = ring_params.verifier_key_from_commitment(commitment);
-    ring_params.verifier(verifier_key)
+/// verifier key component of the SRS (and cached, see [`cache`]). Constructing
+/// the verifier clones the full `PiopParams` (sized to the ring's domain), so
+/// callers verifying many signatures against the same ring should build it
+/// once and reuse it.
+fn build_ring_verifier(
+    ring_size: RingSize,
+    verifier_key: RingVerifierKey,
+) -> Result<RingVerifier, Error> {
+    Ok(ring_proof_params(ring_size)?.verifier(verifier_key))
 }
 
 /// Construct VRF Input Point from arbitrary data (section 1.2).
@@ -103,24 +251,70 @@ pub enum Error {
     InvalidSignature,
     /// Signature verification failed.
     VerificationFailure,
+    /// Invalid or insufficient PCS (SRS) parameters.
+    InvalidPcsParams,
+    /// PCS parameters were not supplied and there are no embedded ones.
+    MissingPcsParams,
+    /// PCS parameters can only be supplied once, before first use.
+    PcsParamsAlreadySet,
+    /// Corrupted, incompatible or insufficient ring parameters snapshot.
+    InvalidRingParams,
+    /// Ring parameters for that ring size were already derived or loaded.
+    RingParamsAlreadySet,
+    /// Invalid secret key encoding.
+    InvalidSecretKey,
+    /// No secret key with that handle (or it was released).
+    UnknownKeyHandle,
+    /// Keystore file could not be read or written.
+    Keystore(keystore::Error),
+    /// VRF output hash is not below the threshold, so no proof was generated.
+    OutputAboveThreshold,
+    /// Requested VRF output hash length is zero or above [`MAX_VRF_OUTPUT_HASH_SIZE`].
+    InvalidOutputLength,
+    /// The prover's public key is not in the ring.
+    ProverKeyNotInRing,
+    /// The prover's public key appears more than once in the ring.
+    DuplicateProverKey,
+    /// Key index outside of the ring.
+    InvalidKeyIndex,
+    /// The ed25519 keys don't match the ring keys one to one.
+    MismatchedValidatorKeys,
+    /// The ring has neither the tiny nor the full ring size.
+    UnsupportedRingSize,
 }
 
 /// Ring commitment type alias.
 pub type RingCommitment = ark_vrf::ring::RingCommitment<BandersnatchSha512Ell2>;
 
+/// Size of the VRF output hash (ticket id, entropy) used by JAM.
+pub const VRF_OUTPUT_HASH_SIZE: usize = 32;
+
+/// Size of the full VRF output hash.
+pub const MAX_VRF_OUTPUT_HASH_SIZE: usize = 64;
+
 /// Size of a public key in bytes.
 pub const PUBLIC_KEY_SIZE: usize = 32;
 
 /// Size of an IETF VRF signature in bytes.
 pub const IETF_SIGNATURE_SIZE: usize = 96;
 
+/// Size of a Pedersen VRF signature in bytes.
+pub const PEDERSEN_SIGNATURE_SIZE: usize = 192;
+
+/// Size of a Pedersen blinding factor (and of the blinded key commitment) in bytes.
+pub const PEDERSEN_BLINDING_SIZE: usize = 32;
+
 /// Size of a Ring VRF signature in bytes.
 pub const RING_SIGNATURE_SIZE: usize = 784;
 
 /// Deserialize a public key from bytes, returning the padding point on failure.
 pub fn deserialize_public_key(chunk: &[u8]) -> Public {
-    Public::deserialize_compressed_unchecked(chunk)
-        .unwrap_or_else(|_| Public::from_affine(RingProofParams::padding_point()))
+    Public::deserialize_compressed_unchecked(chunk).unwrap_or_else(|_| padding_public_key())
+}
+
+/// Public key standing for a missing or excluded ring member (the padding point).
+pub fn padding_public_key() -> Public {
+    Public::from_affine(RingProofParams::padding_point())
 }
 
 /// Extract VRF output hash from output.
@@ -130,6 +324,15 @@ pub fn copy_vrf_output_hash(output: Output) -> [u8; 32] {
     vrf_output_hash
 }
 
+/// Extract the first `output_len` bytes of the VRF output hash, up to
+/// [`MAX_VRF_OUTPUT_HASH_SIZE`].
+pub fn vrf_output_hash_with_len(output: Output, output_len: usize) -> Result<Vec<u8>, Error> {
+    if !(1..=MAX_VRF_OUTPUT_HASH_SIZE).contains(&output_len) {
+        return Err(Error::InvalidOutputLength);
+    }
+    Ok(output.hash()[..output_len].to_vec())
+}
+
 /// Verifier for VRF signatures.
 pub struct Verifier;
 
@@ -138,7 +341,13 @@ impl Verifier {
     ///
     /// Used for tickets verification.
     ///
-    /// On success returns the VRF output hash.
+    /// The verifier is taken from the ring cache when the same commitment was
+    /// used recently.
+    ///
+    /// On success returns the VRF output hash. It is the ticket id, which the
+    /// protocol defines as 32 bytes, so unlike [`compute_vrf_output_hash`] there
+    /// is no output length parameter; [`vrf_output_from_signature`] gives other
+    /// lengths once the signature is verified.
     pub fn ring_vrf_verify(
         ring_size: RingSize,
         commitment: RingCommitment,
@@ -146,7 +355,7 @@ impl Verifier {
         aux_data: &[u8],
         signature: &[u8],
     ) -> Result<[u8; 32], Error> {
-        let verifier = build_ring_verifier(ring_size, commitment);
+        let verifier = RING_CACHE.ring_verifier(ring_size, commitment)?;
         Self::ring_vrf_verify_with(&verifier, vrf_input_data, aux_data, signature)
     }
 
@@ -181,13 +390,25 @@ impl Verifier {
     ///
     /// Used for ticket claim verification during block import.
     ///
-    /// On success returns the VRF output hash.
+    /// On success returns the VRF output hash, fixed at 32 bytes as it's
+    /// compared with the claimed ticket id. Use [`vrf_output_from_signature`]
+    /// on the verified signature for a hash of another length.
     pub fn ietf_vrf_verify(
         vrf_input_data: &[u8],
         aux_data: &[u8],
         signature: &[u8],
         signer_public_key: Public,
     ) -> Result<[u8; 32], Error> {
+        Self::ietf_vrf_verify_output(vrf_input_data, aux_data, signature, signer_public_key)
+            .map(copy_vrf_output_hash)
+    }
+
+    fn ietf_vrf_verify_output(
+        vrf_input_data: &[u8],
+        aux_data: &[u8],
+        signature: &[u8],
+        signer_public_key: Public,
+    ) -> Result<Output, Error> {
         use ark_vrf::ietf::Verifier as _;
 
         let signature = IetfVrfSignature::deserialize_compressed_unchecked(signature)
@@ -203,20 +424,146 @@ impl Verifier {
             return Err(Error::VerificationFailure);
         }
 
-        Ok(copy_vrf_output_hash(output))
+        Ok(output)
+    }
+
+    /// Non-Anonymous VRF signature verification of many signatures at once.
+    ///
+    /// Returns the same result for each signature as [`Self::ietf_vrf_verify`],
+    /// but the proofs are verified as one batch (see the `ietf_batch` module).
+    /// IETF proofs can't be checked as one random linear combination, so the
+    /// results need no individual re-verification to tell which failed.
+    pub fn batch_ietf_vrf_verify(signatures: &[IetfVrfVerifyData]) -> Vec<Result<[u8; 32], Error>> {
+        let mut checks = Vec::with_capacity(signatures.len());
+        let parsed: Vec<_> = signatures
+            .iter()
+            .map(|data| {
+                ietf_batch::IetfCheck::new(
+                    data.signer_public_key,
+                    data.vrf_input_data,
+                    data.aux_data,
+                    data.signature,
+                )
+                .map(|check| checks.push(check))
+            })
+            .collect();
+
+        let valid = ietf_batch::verify_ietf_batch(&checks);
+        let mut verified = valid.into_iter().zip(&checks);
+
+        parsed
+            .into_iter()
+            .map(|parsed| {
+                parsed?;
+                let (valid, check) = verified.next().expect("one check per parsed signature");
+                if !valid {
+                    return Err(Error::VerificationFailure);
+                }
+                Ok(copy_vrf_output_hash(check.output))
+            })
+            .collect()
+    }
+
+    /// Blinded-key VRF signature verification.
+    ///
+    /// The signer is only known through the blinded key commitment carried by
+    /// the proof, which can be opened with [`pedersen_key_commitment`].
+    ///
+    /// On success returns the VRF output hash and the blinded key commitment.
+    pub fn pedersen_vrf_verify(
+        vrf_input_data: &[u8],
+        aux_data: &[u8],
+        signature: &[u8],
+    ) -> Result<([u8; 32], [u8; PEDERSEN_BLINDING_SIZE]), Error> {
+        use ark_vrf::pedersen::Verifier as _;
+
+        let signature = PedersenVrfSignature::deserialize_compressed_unchecked(signature)
+            .map_err(|_| Error::InvalidSignature)?;
+
+        let input = vrf_input_point(vrf_input_data)?;
+        let output = signature.output;
+
+        if Public::verify(input, output, aux_data, &signature.proof).is_err() {
+            return Err(Error::VerificationFailure);
+        }
+
+        let mut key_commitment = [0u8; PEDERSEN_BLINDING_SIZE];
+        signature
+            .proof
+            .key_commitment()
+            .serialize_compressed(&mut key_commitment[..])
+            .map_err(|_| Error::InvalidSignature)?;
+
+        Ok((copy_vrf_output_hash(output), key_commitment))
     }
 }
 
 /// Generate ring commitment given a slice of public keys.
+///
+/// Commitments of recently used rings are served from the ring cache.
 pub fn compute_ring_commitment(keys: &[Public], ring_size: RingSize) -> Result<Vec<u8>, Error> {
-    let pts: Vec<_> = keys.iter().map(|pk| pk.0).collect();
-    let verifier_key = ring_proof_params(ring_size).verifier_key(&pts);
-    let commitment = verifier_key.commitment();
-    let mut buf = Vec::new();
-    commitment
-        .serialize_compressed(&mut buf)
-        .map_err(|_| Error::InvalidSignature)?;
-    Ok(buf)
+    RING_CACHE.ring_commitment(keys, ring_size)
+}
+
+/// Validators to exclude from a ring.
+pub enum Offenders<'a> {
+    /// Positions of the offenders in the ring.
+    Indices(&'a [usize]),
+    /// Ed25519 keys of the offenders, matched against the ed25519 key of each
+    /// ring member (`validator_keys`, in ring order).
+    Ed25519Keys {
+        validator_keys: &'a [[u8; 32]],
+        offenders: &'a [[u8; 32]],
+    },
+}
+
+/// Generate the ring commitment with the keys of offenders replaced by the
+/// padding point, as the Gray Paper's `Φ` function does before the ring is
+/// committed to.
+pub fn ring_commitment_with_offenders(
+    keys: &[Public],
+    offenders: Offenders,
+    ring_size: RingSize,
+) -> Result<Vec<u8>, Error> {
+    let mut keys = keys.to_vec();
+    match offenders {
+        Offenders::Indices(indices) => {
+            for &index in indices {
+                *keys.get_mut(index).ok_or(Error::InvalidKeyIndex)? = padding_public_key();
+            }
+        }
+        Offenders::Ed25519Keys {
+            validator_keys,
+            offenders,
+        } => {
+            if validator_keys.len() != keys.len() {
+                return Err(Error::MismatchedValidatorKeys);
+            }
+            for (key, validator_key) in keys.iter_mut().zip(validator_keys) {
+                if offenders.contains(validator_key) {
+                    *key = padding_public_key();
+                }
+            }
+        }
+    }
+    compute_ring_commitment(&keys, ring_size)
+}
+
+/// Compute the ring commitment of `previous_keys` with some keys replaced,
+/// given as `(index, new key)` pairs applied in order.
+///
+/// Only the replaced keys are committed to, on top of `previous_commitment`,
+/// which must be the commitment to `previous_keys` for the same ring size:
+/// the result is then the same as [`compute_ring_commitment`] of the updated
+/// keys. The first update for a ring size also derives the SRS in Lagrangian
+/// form, which costs about as much as a full commitment.
+pub fn update_ring_commitment(
+    previous_keys: &[Public],
+    previous_commitment: &[u8],
+    replacements: &[(usize, Public)],
+    ring_size: RingSize,
+) -> Result<Vec<u8>, Error> {
+    ring_update::update_ring_commitment(previous_keys, previous_commitment, replacements, ring_size)
 }
 
 /// Derive a public key from a seed.
@@ -236,7 +583,28 @@ pub fn generate_ietf_seal(
     input: &[u8],
     aux_data: &[u8],
 ) -> Result<Vec<u8>, Error> {
-    let secret = Secret::from_seed(secret_seed);
+    ietf_seal(&Secret::from_seed(secret_seed), input, aux_data)
+}
+
+/// Generate an IETF VRF seal (signature) with an imported secret key.
+pub fn generate_ietf_seal_with_key(
+    key: KeyHandle,
+    input: &[u8],
+    aux_data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    ietf_seal(&*keys::secret_key(key)?, input, aux_data)
+}
+
+fn ietf_seal(secret: &Secret, input: &[u8], aux_data: &[u8]) -> Result<Vec<u8>, Error> {
+    ietf_seal_with_output(secret, input, aux_data).map(|(seal, _)| seal)
+}
+
+/// Serialized IETF VRF signature together with its VRF output.
+fn ietf_seal_with_output(
+    secret: &Secret,
+    input: &[u8],
+    aux_data: &[u8],
+) -> Result<(Vec<u8>, Output), Error> {
     let input_point = Input::new(input).ok_or(Error::InvalidPointData)?;
 
     let output = secret.output(input_point);
@@ -246,151 +614,687 @@ pub fn generate_ietf_seal(
     let mut result = Vec::new();
     sig.serialize_compressed(&mut result)
         .map_err(|_| Error::InvalidSignature)?;
-    Ok(result)
+    Ok((result, output))
 }
 
-/// Compute VRF output hash from a secret seed and input data.
-pub fn compute_vrf_output_hash(secret_seed: &[u8], input: &[u8]) -> Result<[u8; 32], Error> {
-    let secret = Secret::from_seed(secret_seed);
-    let input_point = Input::new(input).ok_or(Error::InvalidPointData)?;
+/// Generate a Pedersen VRF signature, proving the output against a blinded public key.
+///
+/// Returns the signature and the blinding factor, which opens the key
+/// commitment (see [`pedersen_key_commitment`]).
+pub fn generate_pedersen_vrf(
+    secret_seed: &[u8],
+    input: &[u8],
+    aux_data: &[u8],
+) -> Result<(Vec<u8>, [u8; PEDERSEN_BLINDING_SIZE]), Error> {
+    pedersen_vrf(&Secret::from_seed(secret_seed), input, aux_data)
+}
+
+/// Generate a Pedersen VRF signature with an imported secret key.
+pub fn generate_pedersen_vrf_with_key(
+    key: KeyHandle,
+    input: &[u8],
+    aux_data: &[u8],
+) -> Result<(Vec<u8>, [u8; PEDERSEN_BLINDING_SIZE]), Error> {
+    pedersen_vrf(&*keys::secret_key(key)?, input, aux_data)
+}
+
+fn pedersen_vrf(
+    secret: &Secret,
+    input: &[u8],
+    aux_data: &[u8],
+) -> Result<(Vec<u8>, [u8; PEDERSEN_BLINDING_SIZE]), Error> {
+    let input_point = vrf_input_point(input)?;
+
     let output = secret.output(input_point);
-    Ok(copy_vrf_output_hash(output))
+    let (proof, blinding) = ark_vrf::pedersen::Prover::prove(secret, input_point, output, aux_data);
+
+    let sig = PedersenVrfSignature { output, proof };
+    let mut signature = Vec::new();
+    sig.serialize_compressed(&mut signature)
+        .map_err(|_| Error::InvalidSignature)?;
+
+    let mut blinding_bytes = [0u8; PEDERSEN_BLINDING_SIZE];
+    blinding
+        .serialize_compressed(&mut blinding_bytes[..])
+        .map_err(|_| Error::InvalidSignature)?;
+
+    Ok((signature, blinding_bytes))
 }
 
-/// Verify both header seal and entropy source in a single call.
-pub fn verify_header_seals_impl(
-    signer_key: &[u8],
-    seal_data: &[u8],
-    seal_payload: &[u8],
-    unsealed_header: &[u8],
-    entropy_data: &[u8],
-    entropy_prefix: &[u8],
-) -> Result<([u8; 32], [u8; 32]), Error> {
-    let public_key = deserialize_public_key(signer_key);
+/// Compute the blinded key commitment `public_key + blinding * B` of a Pedersen VRF proof.
+///
+/// Used to check that a Pedersen VRF signature was produced by the owner of a
+/// given public key, once the blinding factor is revealed.
+pub fn pedersen_key_commitment(
+    public_key: &[u8],
+    blinding: &[u8],
+) -> Result<[u8; PEDERSEN_BLINDING_SIZE], Error> {
+    use ark_vrf::pedersen::PedersenSuite;
+    use ark_vrf::reexports::ark_ec::CurveGroup;
+
+    let public_key =
+        Public::deserialize_compressed(public_key).map_err(|_| Error::InvalidPointData)?;
+    let blinding =
+        ScalarField::deserialize_compressed(blinding).map_err(|_| Error::InvalidPointData)?;
+
+    let commitment =
+        (public_key.0 + BandersnatchSha512Ell2::BLINDING_BASE * blinding).into_affine();
+    let mut result = [0u8; PEDERSEN_BLINDING_SIZE];
+    commitment
+        .serialize_compressed(&mut result[..])
+        .map_err(|_| Error::InvalidPointData)?;
+    Ok(result)
+}
 
-    let seal = Verifier::ietf_vrf_verify(seal_payload, unsealed_header, seal_data, public_key)?;
+/// Compute the first `output_len` bytes of the VRF output hash from a secret
+/// seed and input data.
+pub fn compute_vrf_output_hash(
+    secret_seed: &[u8],
+    input: &[u8],
+    output_len: usize,
+) -> Result<Vec<u8>, Error> {
+    vrf_output_hash_with_len(
+        secret_output(&Secret::from_seed(secret_seed), input)?,
+        output_len,
+    )
+}
 
-    let mut entropy_payload = Vec::with_capacity(entropy_prefix.len() + seal.len());
-    entropy_payload.extend_from_slice(entropy_prefix);
-    entropy_payload.extend_from_slice(&seal);
+/// Compute the first `output_len` bytes of the VRF output hash from an
+/// imported secret key and input data.
+pub fn compute_vrf_output_hash_with_key(
+    key: KeyHandle,
+    input: &[u8],
+    output_len: usize,
+) -> Result<Vec<u8>, Error> {
+    vrf_output_hash_with_len(secret_output(&*keys::secret_key(key)?, input)?, output_len)
+}
 
-    let entropy = Verifier::ietf_vrf_verify(&entropy_payload, &[], entropy_data, public_key)?;
+/// First `output_len` bytes of the VRF output hash of an IETF or ring VRF
+/// signature.
+///
+/// The signature is only decoded, not verified, so this is meant for
+/// signatures that were already verified, e.g. to recompute the entropy of a
+/// header again.
+pub fn vrf_output_from_signature(signature: &[u8], output_len: usize) -> Result<Vec<u8>, Error> {
+    vrf_output_hash_with_len(signature_output(signature)?, output_len)
+}
 
-    Ok((seal, entropy))
+/// Decode the output of a signature, telling the signature types apart by their size.
+fn signature_output(signature: &[u8]) -> Result<Output, Error> {
+    let output = match signature.len() {
+        IETF_SIGNATURE_SIZE => IetfVrfSignature::deserialize_compressed_unchecked(signature)
+            .map(|signature| signature.output),
+        RING_SIGNATURE_SIZE => RingVrfSignature::deserialize_compressed_unchecked(signature)
+            .map(|signature| signature.output),
+        _ => return Err(Error::InvalidSignature),
+    };
+    output.map_err(|_| Error::InvalidSignature)
 }
 
-/// Verify a seal and return the VRF output hash.
-pub fn verify_seal_impl(
-    signer_key: &[u8],
-    seal_data: &[u8],
-    payload: &[u8],
-    aux_data: &[u8],
-) -> Result<[u8; 32], Error> {
-    let public_key = deserialize_public_key(signer_key);
-    Verifier::ietf_vrf_verify(payload, aux_data, seal_data, public_key)
+fn secret_output(secret: &Secret, input: &[u8]) -> Result<Output, Error> {
+    let input_point = Input::new(input).ok_or(Error::InvalidPointData)?;
+    Ok(secret.output(input_point))
 }
 
-fn input_chunk_count(inputs_data: &[u8], vrf_input_data_len: usize) -> usize {
-    if vrf_input_data_len == 0 {
-        return 1;
-    }
-    inputs_data.len().div_ceil(vrf_input_data_len)
+/// Compute the VRF output hashes (ticket ids) of inputs of `vrf_input_data_len` bytes each.
+///
+/// Much cheaper than generating the ring proofs, so it can be used to pick
+/// the attempts worth proving.
+pub fn compute_vrf_output_hashes(
+    secret_seed: &[u8],
+    inputs_data: &[u8],
+    vrf_input_data_len: usize,
+) -> Vec<Result<[u8; 32], Error>> {
+    vrf_output_hashes(
+        &Secret::from_seed(secret_seed),
+        inputs_data,
+        vrf_input_data_len,
+    )
 }
 
-fn ring_vrf_error_results(count: usize, error: Error) -> Vec<Result<Vec<u8>, Error>> {
-    vec![Err(error); count]
+fn vrf_output_hashes(
+    secret: &Secret,
+    inputs_data: &[u8],
+    vrf_input_data_len: usize,
+) -> Vec<Result<[u8; 32], Error>> {
+    vrf_outputs(secret, inputs_data, vrf_input_data_len)
+        .into_iter()
+        .map(|io| io.map(|(_, output)| copy_vrf_output_hash(output)))
+        .collect()
 }
 
-fn generate_ring_vrf_signatures_with_prover(
+/// VRF input and output points for an input, or why it has none.
+type VrfInputOutput = Result<(Input, Output), Error>;
+
+/// VRF input and output points of inputs of `vrf_input_data_len` bytes each.
+fn vrf_outputs(
     secret: &Secret,
-    prover: &RingProver,
     inputs_data: &[u8],
     vrf_input_data_len: usize,
-) -> Vec<Result<Vec<u8>, Error>> {
+) -> Vec<VrfInputOutput> {
     if vrf_input_data_len == 0 {
-        return ring_vrf_error_results(1, Error::InvalidPointData);
+        return vec![Err(Error::InvalidPointData)];
     }
-
     inputs_data
         .chunks(vrf_input_data_len)
         .map(|vrf_input_data| {
             if vrf_input_data.len() < vrf_input_data_len {
                 return Err(Error::InvalidPointData);
             }
-
             let input = vrf_input_point(vrf_input_data)?;
-            let output = secret.output(input);
-
-            let proof = ark_vrf::ring::Prover::prove(secret, input, output, &[], prover);
-
-            let sig = RingVrfSignature { output, proof };
-            let mut signature = Vec::new();
-            sig.serialize_compressed(&mut signature)
-                .map_err(|_| Error::InvalidSignature)?;
-            Ok(signature)
+            Ok((input, secret.output(input)))
         })
         .collect()
 }
 
-/// Generate one anonymous ring VRF signature for a concrete input.
+/// Generate both the header seal and the entropy source signature.
 ///
-/// Callers that model ticket attempts in the input bytes can use this to
-/// generate an exact attempt, instead of generating the whole `0..X` range.
-pub fn generate_ring_vrf_impl(
-    ring_keys: &[Public],
-    prover_key_index: usize,
+/// The counterpart of [`verify_header_seals_impl`]: the seal signs
+/// `seal_payload` with the unsealed header as aux data, and the entropy
+/// source signs `entropy_prefix || seal output hash` with no aux data.
+pub fn generate_header_seals(
     secret_seed: &[u8],
-    vrf_input_data: &[u8],
-) -> Result<Vec<u8>, Error> {
-    let mut results = batch_generate_ring_vrf_impl(
-        ring_keys,
-        prover_key_index,
-        secret_seed,
-        vrf_input_data,
-        vrf_input_data.len(),
-    );
+    seal_payload: &[u8],
+    unsealed_header: &[u8],
+    entropy_prefix: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    header_seals(
+        &Secret::from_seed(secret_seed),
+        seal_payload,
+        unsealed_header,
+        entropy_prefix,
+    )
+}
 
-    results.pop().unwrap_or(Err(Error::InvalidPointData))
+/// Generate both the header seal and the entropy source signature with an imported key.
+pub fn generate_header_seals_with_key(
+    key: KeyHandle,
+    seal_payload: &[u8],
+    unsealed_header: &[u8],
+    entropy_prefix: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    header_seals(
+        &*keys::secret_key(key)?,
+        seal_payload,
+        unsealed_header,
+        entropy_prefix,
+    )
 }
 
-/// Batch generate anonymous ring VRF signatures.
-pub fn batch_generate_ring_vrf_impl(
-    ring_keys: &[Public],
-    prover_key_index: usize,
-    secret_seed: &[u8],
-    inputs_data: &[u8],
-    vrf_input_data_len: usize,
-) -> Vec<Result<Vec<u8>, Error>> {
-    let ring_size = RingSize::from_size(ring_keys.len());
-    let secret = Secret::from_seed(secret_seed);
-    let ring_params = ring_proof_params(ring_size);
-    let pts: Vec<_> = ring_keys.iter().map(|pk| pk.0).collect();
-    let num_inputs = input_chunk_count(inputs_data, vrf_input_data_len);
+fn header_seals(
+    secret: &Secret,
+    seal_payload: &[u8],
+    unsealed_header: &[u8],
+    entropy_prefix: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let (seal, seal_output) = ietf_seal_with_output(secret, seal_payload, unsealed_header)?;
+    let seal_output = copy_vrf_output_hash(seal_output);
 
-    if prover_key_index >= ring_keys.len() {
-        return ring_vrf_error_results(num_inputs, Error::InvalidSignature);
-    }
+    let mut entropy_payload = Vec::with_capacity(entropy_prefix.len() + seal_output.len());
+    entropy_payload.extend_from_slice(entropy_prefix);
+    entropy_payload.extend_from_slice(&seal_output);
 
-    let prover_key = ring_params.prover_key(&pts);
-    let prover = ring_params.prover(prover_key, prover_key_index);
+    let entropy = ietf_seal(secret, &entropy_payload, &[])?;
 
-    generate_ring_vrf_signatures_with_prover(&secret, &prover, inputs_data, vrf_input_data_len)
+    Ok((seal, entropy))
 }
 
-/// Batch generate anonymous ring VRF signatures for multiple validators.
+/// Verify both header seal and entropy source in a single call.
+///
+/// Both output hashes are 32 bytes: the entropy source signs the seal output
+/// hash, and the protocol defines that payload with a 32-byte hash, so another
+/// length would make every entropy source fail to verify.
+pub fn verify_header_seals_impl(
+    signer_key: &[u8],
+    seal_data: &[u8],
+    seal_payload: &[u8],
+    unsealed_header: &[u8],
+    entropy_data: &[u8],
+    entropy_prefix: &[u8],
+) -> Result<([u8; 32], [u8; 32]), Error> {
+    let public_key = deserialize_public_key(signer_key);
+
+    let seal = Verifier::ietf_vrf_verify(seal_payload, unsealed_header, seal_data, public_key)?;
+
+    let mut entropy_payload = Vec::with_capacity(entropy_prefix.len() + seal.len());
+    entropy_payload.extend_from_slice(entropy_prefix);
+    entropy_payload.extend_from_slice(&seal);
+
+    let entropy = Verifier::ietf_vrf_verify(&entropy_payload, &[], entropy_data, public_key)?;
+
+    Ok((seal, entropy))
+}
+
+/// One signature, as given to [`Verifier::batch_ietf_vrf_verify`].
+pub struct IetfVrfVerifyData<'a> {
+    pub signer_public_key: Public,
+    pub vrf_input_data: &'a [u8],
+    pub aux_data: &'a [u8],
+    pub signature: &'a [u8],
+}
+
+/// Split signatures laid out as `signer_key || signature` followed by the
+/// length-prefixed (u32 LE) `vrf_input_data` and `aux_data`.
+fn parse_ietf_signatures(signatures_data: &[u8]) -> Result<Vec<IetfVrfVerifyData<'_>>, Error> {
+    const FIXED_SIZE: usize = PUBLIC_KEY_SIZE + IETF_SIGNATURE_SIZE;
+    let mut signatures = Vec::new();
+    let mut rest = signatures_data;
+
+    while !rest.is_empty() {
+        if rest.len() < FIXED_SIZE {
+            return Err(Error::InvalidSignature);
+        }
+        let (signer_key, tail) = rest.split_at(PUBLIC_KEY_SIZE);
+        let (signature, tail) = tail.split_at(IETF_SIGNATURE_SIZE);
+        let (vrf_input_data, tail) = split_length_prefixed(tail)?;
+        let (aux_data, tail) = split_length_prefixed(tail)?;
+
+        signatures.push(IetfVrfVerifyData {
+            signer_public_key: deserialize_public_key(signer_key),
+            vrf_input_data,
+            aux_data,
+            signature,
+        });
+        rest = tail;
+    }
+
+    Ok(signatures)
+}
+
+/// Seals of one header, as given to [`verify_header_seals_impl`].
+pub struct HeaderSealsData<'a> {
+    pub signer_key: &'a [u8],
+    pub seal_data: &'a [u8],
+    pub seal_payload: &'a [u8],
+    pub unsealed_header: &'a [u8],
+    pub entropy_data: &'a [u8],
+    pub entropy_prefix: &'a [u8],
+}
+
+/// Seal and entropy source VRF output hashes of a header.
+pub type HeaderSealsHashes = ([u8; 32], [u8; 32]);
+
+/// Verify the seals of many headers at once.
+///
+/// Returns the same seal and entropy output hashes as calling
+/// [`verify_header_seals_impl`] for each header, but both proofs of all the
+/// headers are verified as one batch (see the `ietf_batch` module).
+pub fn batch_verify_header_seals_impl(
+    headers: &[HeaderSealsData],
+) -> Vec<Result<HeaderSealsHashes, Error>> {
+    let mut checks = Vec::with_capacity(2 * headers.len());
+    let parsed: Vec<_> = headers
+        .iter()
+        .map(|header| header_seal_checks(header).map(|pair| checks.extend(pair)))
+        .collect();
+
+    let valid = ietf_batch::verify_ietf_batch(&checks);
+    let mut verified = valid.chunks_exact(2).zip(checks.chunks_exact(2));
+
+    parsed
+        .into_iter()
+        .map(|parsed| {
+            parsed?;
+            let (valid, checks) = verified.next().expect("two checks per parsed header");
+            if !(valid[0] && valid[1]) {
+                return Err(Error::VerificationFailure);
+            }
+            Ok((
+                copy_vrf_output_hash(checks[0].output),
+                copy_vrf_output_hash(checks[1].output),
+            ))
+        })
+        .collect()
+}
+
+/// The seal and entropy source proofs of a header.
+fn header_seal_checks<'a>(
+    header: &HeaderSealsData<'a>,
+) -> Result<[ietf_batch::IetfCheck<'a>; 2], Error> {
+    let public = deserialize_public_key(header.signer_key);
+    let seal = ietf_batch::IetfCheck::new(
+        public,
+        header.seal_payload,
+        header.unsealed_header,
+        header.seal_data,
+    )?;
+    let entropy_payload = [header.entropy_prefix, &copy_vrf_output_hash(seal.output)].concat();
+    let entropy = ietf_batch::IetfCheck::new(public, &entropy_payload, &[], header.entropy_data)?;
+
+    Ok([seal, entropy])
+}
+
+/// Split a `len (u32 LE) || data` field off the front of `data`.
+fn split_length_prefixed(data: &[u8]) -> Result<(&[u8], &[u8]), Error> {
+    if data.len() < 4 {
+        return Err(Error::InvalidSignature);
+    }
+    let (len, rest) = data.split_at(4);
+    let len = u32::from_le_bytes(len.try_into().expect("split at 4 bytes")) as usize;
+    if rest.len() < len {
+        return Err(Error::InvalidSignature);
+    }
+    Ok(rest.split_at(len))
+}
+
+/// Split headers laid out as `signer_key || seal || entropy` followed by the
+/// length-prefixed (u32 LE) `seal_payload`, `unsealed_header` and `entropy_prefix`.
+fn parse_header_seals(headers_data: &[u8]) -> Result<Vec<HeaderSealsData<'_>>, Error> {
+    const FIXED_SIZE: usize = PUBLIC_KEY_SIZE + 2 * IETF_SIGNATURE_SIZE;
+    let mut headers = Vec::new();
+    let mut rest = headers_data;
+
+    while !rest.is_empty() {
+        if rest.len() < FIXED_SIZE {
+            return Err(Error::InvalidSignature);
+        }
+        let (signer_key, tail) = rest.split_at(PUBLIC_KEY_SIZE);
+        let (seal_data, tail) = tail.split_at(IETF_SIGNATURE_SIZE);
+        let (entropy_data, tail) = tail.split_at(IETF_SIGNATURE_SIZE);
+        let (seal_payload, tail) = split_length_prefixed(tail)?;
+        let (unsealed_header, tail) = split_length_prefixed(tail)?;
+        let (entropy_prefix, tail) = split_length_prefixed(tail)?;
+
+        headers.push(HeaderSealsData {
+            signer_key,
+            seal_data,
+            seal_payload,
+            unsealed_header,
+            entropy_data,
+            entropy_prefix,
+        });
+        rest = tail;
+    }
+
+    Ok(headers)
+}
+
+/// Verify a seal and return the first `output_len` bytes of the VRF output hash.
+pub fn verify_seal_impl(
+    signer_key: &[u8],
+    seal_data: &[u8],
+    payload: &[u8],
+    aux_data: &[u8],
+    output_len: usize,
+) -> Result<Vec<u8>, Error> {
+    let public_key = deserialize_public_key(signer_key);
+    let output = Verifier::ietf_vrf_verify_output(payload, aux_data, seal_data, public_key)?;
+    vrf_output_hash_with_len(output, output_len)
+}
+
+fn input_chunk_count(inputs_data: &[u8], vrf_input_data_len: usize) -> usize {
+    if vrf_input_data_len == 0 {
+        return 1;
+    }
+    inputs_data.len().div_ceil(vrf_input_data_len)
+}
+
+fn ring_vrf_error_results(count: usize, error: Error) -> Vec<Result<Vec<u8>, Error>> {
+    vec![Err(error); count]
+}
+
+/// Generate ring VRF signatures for the given VRF outputs (see [`vrf_outputs`]),
+/// passing the errors of the inputs without an output through.
+fn generate_ring_vrf_signatures_with_prover(
+    secret: &Secret,
+    prover: &RingProver,
+    outputs: Vec<VrfInputOutput>,
+    aux_data: &[u8],
+) -> Vec<Result<Vec<u8>, Error>> {
+    let generate = |io: VrfInputOutput| {
+        let (input, output) = io?;
+        let proof = ark_vrf::ring::Prover::prove(secret, input, output, aux_data, prover);
+
+        let sig = RingVrfSignature { output, proof };
+        let mut signature = Vec::new();
+        sig.serialize_compressed(&mut signature)
+            .map_err(|_| Error::InvalidSignature)?;
+        Ok(signature)
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        outputs.into_par_iter().map(generate).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        outputs.into_iter().map(generate).collect()
+    }
+}
+
+/// Generate one anonymous ring VRF signature for a concrete input.
+///
+/// Callers that model ticket attempts in the input bytes can use this to
+/// generate an exact attempt, instead of generating the whole `0..X` range.
+///
+/// `aux_data` is bound into the proof and must be given again on verification
+/// (empty for Safrole tickets).
+pub fn generate_ring_vrf_impl(
+    ring_keys: &[Public],
+    prover_key_index: usize,
+    secret_seed: &[u8],
+    vrf_input_data: &[u8],
+    aux_data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let mut results = batch_generate_ring_vrf_impl(
+        ring_keys,
+        prover_key_index,
+        secret_seed,
+        vrf_input_data,
+        vrf_input_data.len(),
+        aux_data,
+    );
+
+    results.pop().unwrap_or(Err(Error::InvalidPointData))
+}
+
+/// Generate one anonymous ring VRF signature with an imported secret key.
+///
+/// See [`generate_ring_vrf_impl`].
+pub fn generate_ring_vrf_with_key_impl(
+    ring_keys: &[Public],
+    prover_key_index: usize,
+    key: KeyHandle,
+    vrf_input_data: &[u8],
+    aux_data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let mut results = batch_generate_ring_vrf_with_key_impl(
+        ring_keys,
+        prover_key_index,
+        key,
+        vrf_input_data,
+        vrf_input_data.len(),
+        aux_data,
+    );
+
+    results.pop().unwrap_or(Err(Error::InvalidPointData))
+}
+
+/// Position of the public key derived from `secret_seed` in the ring.
+///
+/// Fails with [`Error::ProverKeyNotInRing`] if the key is absent and with
+/// [`Error::DuplicateProverKey`] if it is there more than once.
+pub fn locate_prover_key_index(ring_keys: &[Public], secret_seed: &[u8]) -> Result<usize, Error> {
+    let public = Secret::from_seed(secret_seed).public();
+    let mut positions = ring_keys
+        .iter()
+        .enumerate()
+        .filter(|(_, key)| **key == public)
+        .map(|(index, _)| index);
+
+    let index = positions.next().ok_or(Error::ProverKeyNotInRing)?;
+    if positions.next().is_some() {
+        return Err(Error::DuplicateProverKey);
+    }
+    Ok(index)
+}
+
+/// Generate one anonymous ring VRF signature, finding the prover's position
+/// in the ring with [`locate_prover_key_index`].
+///
+/// See [`generate_ring_vrf_impl`].
+pub fn generate_ring_vrf_for_secret_impl(
+    ring_keys: &[Public],
+    secret_seed: &[u8],
+    vrf_input_data: &[u8],
+    aux_data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let prover_key_index = locate_prover_key_index(ring_keys, secret_seed)?;
+    generate_ring_vrf_impl(
+        ring_keys,
+        prover_key_index,
+        secret_seed,
+        vrf_input_data,
+        aux_data,
+    )
+}
+
+/// Batch generate anonymous ring VRF signatures.
+///
+/// The same `aux_data` is bound into every generated signature.
+pub fn batch_generate_ring_vrf_impl(
+    ring_keys: &[Public],
+    prover_key_index: usize,
+    secret_seed: &[u8],
+    inputs_data: &[u8],
+    vrf_input_data_len: usize,
+    aux_data: &[u8],
+) -> Vec<Result<Vec<u8>, Error>> {
+    let secret = Secret::from_seed(secret_seed);
+    batch_generate_ring_vrf_with_secret(
+        ring_keys,
+        prover_key_index,
+        &secret,
+        vrf_outputs(&secret, inputs_data, vrf_input_data_len),
+        aux_data,
+    )
+}
+
+/// Score then prove: batch generate ring VRF signatures only for the inputs
+/// whose output hash (ticket id) is below `threshold`.
+///
+/// Hashes are compared as big-endian numbers. Inputs at or above the
+/// threshold get [`Error::OutputAboveThreshold`] instead of a signature, and
+/// when no input qualifies the ring prover isn't even set up.
+pub fn batch_generate_ring_vrf_below_threshold_impl(
+    ring_keys: &[Public],
+    prover_key_index: usize,
+    secret_seed: &[u8],
+    inputs_data: &[u8],
+    vrf_input_data_len: usize,
+    threshold: &[u8; 32],
+    aux_data: &[u8],
+) -> Vec<Result<Vec<u8>, Error>> {
+    let secret = Secret::from_seed(secret_seed);
+    // The outputs computed for scoring are the ones proven below.
+    let outputs: Vec<_> = vrf_outputs(&secret, inputs_data, vrf_input_data_len)
+        .into_iter()
+        .map(|io| {
+            io.and_then(|(input, output)| {
+                if copy_vrf_output_hash(output) < *threshold {
+                    Ok((input, output))
+                } else {
+                    Err(Error::OutputAboveThreshold)
+                }
+            })
+        })
+        .collect();
+    if !outputs.iter().any(Result::is_ok) {
+        // Nothing to prove, but a bad prover index or ring is still an error.
+        if prover_key_index >= ring_keys.len() {
+            return ring_vrf_error_results(outputs.len(), Error::InvalidSignature);
+        }
+        if let Err(e) = ring_prover_setup(ring_keys) {
+            return ring_vrf_error_results(outputs.len(), e);
+        }
+        // Every input failed or was skipped: pass its error through.
+        return outputs
+            .into_iter()
+            .filter_map(Result::err)
+            .map(Err)
+            .collect();
+    }
+
+    batch_generate_ring_vrf_with_secret(ring_keys, prover_key_index, &secret, outputs, aux_data)
+}
+
+/// Batch generate anonymous ring VRF signatures with an imported secret key.
+///
+/// See [`batch_generate_ring_vrf_impl`].
+pub fn batch_generate_ring_vrf_with_key_impl(
+    ring_keys: &[Public],
+    prover_key_index: usize,
+    key: KeyHandle,
+    inputs_data: &[u8],
+    vrf_input_data_len: usize,
+    aux_data: &[u8],
+) -> Vec<Result<Vec<u8>, Error>> {
+    match keys::secret_key(key) {
+        Ok(secret) => batch_generate_ring_vrf_with_secret(
+            ring_keys,
+            prover_key_index,
+            &secret,
+            vrf_outputs(&secret, inputs_data, vrf_input_data_len),
+            aux_data,
+        ),
+        Err(e) => ring_vrf_error_results(input_chunk_count(inputs_data, vrf_input_data_len), e),
+    }
+}
+
+/// Ring params and (cached) ring prover key for proving in `ring_keys`.
+fn ring_prover_setup(
+    ring_keys: &[Public],
+) -> Result<(&'static RingProofParams, Arc<RingProverKey>), Error> {
+    let ring_size = RingSize::from_key_count(ring_keys.len())?;
+    let ring_params = ring_proof_params(ring_size)?;
+    let prover_key = RING_CACHE.ring_prover_key(ring_keys, ring_size)?;
+    Ok((ring_params, prover_key))
+}
+
+fn batch_generate_ring_vrf_with_secret(
+    ring_keys: &[Public],
+    prover_key_index: usize,
+    secret: &Secret,
+    outputs: Vec<VrfInputOutput>,
+    aux_data: &[u8],
+) -> Vec<Result<Vec<u8>, Error>> {
+    let num_inputs = outputs.len();
+
+    if prover_key_index >= ring_keys.len() {
+        return ring_vrf_error_results(num_inputs, Error::InvalidSignature);
+    }
+
+    let (ring_params, prover_key) = match ring_prover_setup(ring_keys) {
+        Ok(prover_setup) => prover_setup,
+        Err(e) => return ring_vrf_error_results(num_inputs, e),
+    };
+    let prover = ring_params.prover((*prover_key).clone(), prover_key_index);
+
+    generate_ring_vrf_signatures_with_prover(secret, &prover, outputs, aux_data)
+}
+
+/// Batch generate anonymous ring VRF signatures for multiple validators.
 ///
 /// Results are returned validator-major, then input-major:
 /// `validator_0/input_0`, `validator_0/input_1`, ..., `validator_1/input_0`, ...
 ///
-/// This reuses the ring prover key across validators and reuses each validator's
+/// This reuses the (cached) ring prover key across validators and reuses each validator's
 /// prover across all inputs, so it avoids the setup churn of calling
 /// [`batch_generate_ring_vrf_impl`] once per validator.
+///
+/// With the `parallel` feature, validators (and the inputs of each validator)
+/// are proven on the rayon thread pool.
+///
+/// The same `aux_data` is bound into every generated signature.
 pub fn batch_generate_ring_vrf_for_validators_impl(
     ring_keys: &[Public],
     prover_key_indices: &[usize],
     secret_seeds: &[&[u8]],
     inputs_data: &[u8],
     vrf_input_data_len: usize,
+    aux_data: &[u8],
 ) -> Result<Vec<Result<Vec<u8>, Error>>, Error> {
     if prover_key_indices.len() != secret_seeds.len() {
         return Err(Error::InvalidSignature);
@@ -408,46 +1312,99 @@ pub fn batch_generate_ring_vrf_for_validators_impl(
         ));
     }
 
-    let ring_size = RingSize::from_size(ring_keys.len());
-    let ring_params = ring_proof_params(ring_size);
-    let pts: Vec<_> = ring_keys.iter().map(|pk| pk.0).collect();
-    let prover_key = ring_params.prover_key(&pts);
+    let (ring_params, prover_key) = match ring_prover_setup(ring_keys) {
+        Ok(prover_setup) => prover_setup,
+        Err(e) => {
+            return Ok(ring_vrf_error_results(
+                prover_key_indices.len() * num_inputs,
+                e,
+            ));
+        }
+    };
 
-    let mut results = Vec::with_capacity(prover_key_indices.len() * num_inputs);
-    for (&prover_key_index, secret_seed) in prover_key_indices.iter().zip(secret_seeds.iter()) {
+    let generate_for_validator = |(&prover_key_index, secret_seed): (&usize, &&[u8])| {
         if prover_key_index >= ring_keys.len() {
-            results.extend(ring_vrf_error_results(num_inputs, Error::InvalidSignature));
-            continue;
+            return ring_vrf_error_results(num_inputs, Error::InvalidSignature);
         }
 
         let secret = Secret::from_seed(secret_seed);
-        let prover = ring_params.prover(prover_key.clone(), prover_key_index);
-        results.extend(generate_ring_vrf_signatures_with_prover(
+        let prover = ring_params.prover((*prover_key).clone(), prover_key_index);
+        generate_ring_vrf_signatures_with_prover(
             &secret,
             &prover,
-            inputs_data,
-            vrf_input_data_len,
-        ));
+            vrf_outputs(&secret, inputs_data, vrf_input_data_len),
+            aux_data,
+        )
+    };
+
+    // Each validator's results are collected separately and flattened in order,
+    // so the validator-major ordering holds regardless of scheduling.
+    #[cfg(feature = "parallel")]
+    let per_validator: Vec<_> = {
+        use rayon::prelude::*;
+        prover_key_indices
+            .par_iter()
+            .zip(secret_seeds.par_iter())
+            .map(generate_for_validator)
+            .collect()
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    let per_validator: Vec<_> = prover_key_indices
+        .iter()
+        .zip(secret_seeds.iter())
+        .map(generate_for_validator)
+        .collect();
+
+    Ok(per_validator.into_iter().flatten().collect())
+}
+
+/// Ticket data borrowed from a batch verification input.
+struct TicketData<'a> {
+    signature: &'a [u8],
+    vrf_input_data: &'a [u8],
+    aux_data: &'a [u8],
+}
+
+/// Split tickets laid out as `signature || vrf_input || aux_data_len (u32 LE) || aux_data`.
+fn parse_tickets_with_aux(
+    tickets_data: &[u8],
+    vrf_input_data_len: usize,
+) -> Result<Vec<TicketData<'_>>, Error> {
+    let header_size = RING_SIGNATURE_SIZE + vrf_input_data_len + 4;
+    let mut tickets = Vec::new();
+    let mut rest = tickets_data;
+
+    while !rest.is_empty() {
+        if rest.len() < header_size {
+            return Err(Error::InvalidSignature);
+        }
+        let (signature, tail) = rest.split_at(RING_SIGNATURE_SIZE);
+        let (vrf_input_data, tail) = tail.split_at(vrf_input_data_len);
+        let (aux_data_len, tail) = tail.split_at(4);
+        let aux_data_len =
+            u32::from_le_bytes(aux_data_len.try_into().expect("split at 4 bytes")) as usize;
+        if tail.len() < aux_data_len {
+            return Err(Error::InvalidSignature);
+        }
+        let (aux_data, tail) = tail.split_at(aux_data_len);
+
+        tickets.push(TicketData {
+            signature,
+            vrf_input_data,
+            aux_data,
+        });
+        rest = tail;
     }
 
-    Ok(results)
+    Ok(tickets)
 }
 
-/// Batch verify multiple tickets against a single ring.
-///
-/// All tickets are aggregated into a single `ark_vrf::ring::BatchVerifier` and
-/// checked with one amortized pairing + MSM, instead of one pairing per ticket.
-/// Batch verification is inherently all-or-nothing: it yields a single pass/fail
-/// for the whole batch and cannot attribute a failure to a specific ticket.
-///
-/// On success the per-ticket VRF output hashes (entropy) are returned in input
-/// order. The call fails as a whole if the commitment or any signature is
-/// malformed, any VRF input point is invalid, or the batch check does not pass.
-pub fn batch_verify_tickets_impl(
+fn batch_verify_ticket_data<'a>(
+    cache: &SharedRingCache,
     ring_size: RingSize,
     commitment_bytes: &[u8],
-    tickets_data: &[u8],
-    vrf_input_data_len: usize,
+    tickets: impl Iterator<Item = Result<TicketData<'a>, Error>>,
 ) -> Result<Vec<[u8; 32]>, Error> {
     use ark_vrf::ring::BatchVerifier;
 
@@ -458,29 +1415,23 @@ pub fn batch_verify_tickets_impl(
     // full `PiopParams` (sized to the ring's domain, ~2048 for the full ring),
     // so building it per ticket would cause O(ring_size) allocation churn that
     // scales with the validator set and could exhaust memory on a full ring.
-    let verifier = build_ring_verifier(ring_size, commitment);
+    // The batch consumes the verifier, so only its key comes from the cache.
+    let verifier_key = cache.ring_verifier_key(ring_size, commitment)?;
+    let verifier = build_ring_verifier(ring_size, verifier_key)?;
     let mut batch = BatchVerifier::new(verifier);
+    let mut entropy = Vec::with_capacity(tickets.size_hint().0);
 
-    let chunk_size = vrf_input_data_len + RING_SIGNATURE_SIZE;
-    let mut entropy = Vec::with_capacity(tickets_data.len() / chunk_size.max(1));
-
-    for chunk in tickets_data.chunks(chunk_size) {
-        if chunk.len() < chunk_size {
-            return Err(Error::InvalidSignature);
-        }
-
-        let signature_bytes = &chunk[0..RING_SIGNATURE_SIZE];
-        let vrf_input_data = &chunk[RING_SIGNATURE_SIZE..];
-
-        let signature = RingVrfSignature::deserialize_compressed_unchecked(signature_bytes)
+    for ticket in tickets {
+        let ticket = ticket?;
+        let signature = RingVrfSignature::deserialize_compressed_unchecked(ticket.signature)
             .map_err(|_| Error::InvalidSignature)?;
-        let input = vrf_input_point(vrf_input_data)?;
+        let input = vrf_input_point(ticket.vrf_input_data)?;
         let output = signature.output;
 
         // The output hash is only trustworthy once the batch verifies; we record
         // it here and return it only after `batch.verify()` succeeds below.
         entropy.push(copy_vrf_output_hash(output));
-        batch.push(input, output, &[], &signature.proof);
+        batch.push(input, output, ticket.aux_data, &signature.proof);
     }
 
     batch.verify().map_err(|_| Error::VerificationFailure)?;
@@ -488,6 +1439,58 @@ pub fn batch_verify_tickets_impl(
     Ok(entropy)
 }
 
+/// Batch verify multiple tickets against a single ring.
+///
+/// All tickets are aggregated into a single `ark_vrf::ring::BatchVerifier` and
+/// checked with one amortized pairing + MSM, instead of one pairing per ticket.
+/// Batch verification is inherently all-or-nothing: it yields a single pass/fail
+/// for the whole batch and cannot attribute a failure to a specific ticket.
+///
+/// On success the per-ticket VRF output hashes (entropy) are returned in input
+/// order. The call fails as a whole if the commitment or any signature is
+/// malformed, any VRF input point is invalid, or the batch check does not pass.
+pub fn batch_verify_tickets_impl(
+    ring_size: RingSize,
+    commitment_bytes: &[u8],
+    tickets_data: &[u8],
+    vrf_input_data_len: usize,
+) -> Result<Vec<[u8; 32]>, Error> {
+    let chunk_size = vrf_input_data_len + RING_SIGNATURE_SIZE;
+    let tickets = tickets_data.chunks(chunk_size).map(|chunk| {
+        if chunk.len() < chunk_size {
+            return Err(Error::InvalidSignature);
+        }
+        let (signature, vrf_input_data) = chunk.split_at(RING_SIGNATURE_SIZE);
+        Ok(TicketData {
+            signature,
+            vrf_input_data,
+            aux_data: &[],
+        })
+    });
+
+    batch_verify_ticket_data(&RING_CACHE, ring_size, commitment_bytes, tickets)
+}
+
+/// Batch verify multiple tickets carrying per-ticket aux data against a single ring.
+///
+/// Same as [`batch_verify_tickets_impl`], but each ticket is laid out as
+/// `signature || vrf_input || aux_data_len (u32 LE) || aux_data`, with the aux
+/// data bound into the ring proof (as with [`Verifier::ring_vrf_verify`]).
+pub fn batch_verify_tickets_with_aux_impl(
+    ring_size: RingSize,
+    commitment_bytes: &[u8],
+    tickets_data: &[u8],
+    vrf_input_data_len: usize,
+) -> Result<Vec<[u8; 32]>, Error> {
+    let tickets = parse_tickets_with_aux(tickets_data, vrf_input_data_len)?;
+    batch_verify_ticket_data(
+        &RING_CACHE,
+        ring_size,
+        commitment_bytes,
+        tickets.into_iter().map(Ok),
+    )
+}
+
 pub mod ffi {
     //! FFI-ready functions that return `Vec<u8>` with status byte prefix.
     //! These are used by both WASM and native bindings.
@@ -496,12 +1499,12 @@ pub mod ffi {
 
     const RESULT_OK: u8 = 0;
     const RESULT_ERR: u8 = 1;
+    /// Ring VRF signature skipped, since the output is above the threshold.
+    const RESULT_SKIPPED: u8 = 2;
 
     pub fn ring_commitment(keys: &[u8]) -> Vec<u8> {
-        let ring_size = if keys.len() / PUBLIC_KEY_SIZE == RingSize::Full.size() {
-            RingSize::Full
-        } else {
-            RingSize::Tiny
+        let Ok(ring_size) = RingSize::from_key_count(keys.len() / PUBLIC_KEY_SIZE) else {
+            return vec![RESULT_ERR];
         };
 
         let public_keys: Vec<_> = keys
@@ -509,24 +1512,314 @@ pub mod ffi {
             .map(deserialize_public_key)
             .collect();
 
-        match compute_ring_commitment(&public_keys, ring_size) {
-            Ok(commitment) => {
-                let mut result = vec![RESULT_OK];
-                result.extend(commitment);
-                result
-            }
-            Err(_) => vec![RESULT_ERR],
-        }
+        match compute_ring_commitment(&public_keys, ring_size) {
+            Ok(commitment) => {
+                let mut result = vec![RESULT_OK];
+                result.extend(commitment);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    pub fn clear_ring_cache() {
+        super::clear_ring_cache()
+    }
+
+    /// Ring commitment with the keys at `offender_indices` (concatenated
+    /// u32 LE) replaced by the padding point. Returns `status || commitment`.
+    pub fn ring_commitment_with_offenders(keys: &[u8], offender_indices: &[u8]) -> Vec<u8> {
+        if !offender_indices.len().is_multiple_of(4) {
+            return vec![RESULT_ERR];
+        }
+        let indices: Vec<_> = offender_indices
+            .chunks_exact(4)
+            .map(|index| u32::from_le_bytes(index.try_into().expect("chunks of 4 bytes")) as usize)
+            .collect();
+        encode_offenders_commitment(keys, Offenders::Indices(&indices))
+    }
+
+    /// Ring commitment with the keys of validators whose ed25519 key (from
+    /// `ed25519_keys`, in ring order) is in `offenders` replaced by the
+    /// padding point. Returns `status || commitment`.
+    pub fn ring_commitment_with_ed25519_offenders(
+        keys: &[u8],
+        ed25519_keys: &[u8],
+        offenders: &[u8],
+    ) -> Vec<u8> {
+        let (Some(validator_keys), Some(offenders)) = (
+            ed25519_key_chunks(ed25519_keys),
+            ed25519_key_chunks(offenders),
+        ) else {
+            return vec![RESULT_ERR];
+        };
+        encode_offenders_commitment(
+            keys,
+            Offenders::Ed25519Keys {
+                validator_keys: &validator_keys,
+                offenders: &offenders,
+            },
+        )
+    }
+
+    fn ed25519_key_chunks(data: &[u8]) -> Option<Vec<[u8; 32]>> {
+        if !data.len().is_multiple_of(32) {
+            return None;
+        }
+        Some(
+            data.chunks_exact(32)
+                .map(|key| key.try_into().expect("chunks of 32 bytes"))
+                .collect(),
+        )
+    }
+
+    fn encode_offenders_commitment(keys: &[u8], offenders: Offenders) -> Vec<u8> {
+        let Ok(ring_size) = RingSize::from_key_count(keys.len() / PUBLIC_KEY_SIZE) else {
+            return vec![RESULT_ERR];
+        };
+        let public_keys: Vec<_> = keys
+            .chunks(PUBLIC_KEY_SIZE)
+            .map(deserialize_public_key)
+            .collect();
+
+        match super::ring_commitment_with_offenders(&public_keys, offenders, ring_size) {
+            Ok(commitment) => {
+                let mut result = vec![RESULT_OK];
+                result.extend(commitment);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    /// Update the commitment to `previous_keys` with `replacements` laid out
+    /// as `(index (u32 LE) || key (32 bytes))*`.
+    ///
+    /// Returns `status || commitment`.
+    pub fn update_ring_commitment(
+        previous_keys: &[u8],
+        previous_commitment: &[u8],
+        replacements: &[u8],
+    ) -> Vec<u8> {
+        let Ok(ring_size) = RingSize::from_key_count(previous_keys.len() / PUBLIC_KEY_SIZE) else {
+            return vec![RESULT_ERR];
+        };
+        if !replacements.len().is_multiple_of(4 + PUBLIC_KEY_SIZE) {
+            return vec![RESULT_ERR];
+        }
+
+        let public_keys: Vec<_> = previous_keys
+            .chunks(PUBLIC_KEY_SIZE)
+            .map(deserialize_public_key)
+            .collect();
+        let replacements: Vec<_> = replacements
+            .chunks_exact(4 + PUBLIC_KEY_SIZE)
+            .map(|chunk| {
+                let (index, key) = chunk.split_at(4);
+                let index = u32::from_le_bytes(index.try_into().expect("split at 4 bytes"));
+                (index as usize, deserialize_public_key(key))
+            })
+            .collect();
+
+        match super::update_ring_commitment(
+            &public_keys,
+            previous_commitment,
+            &replacements,
+            ring_size,
+        ) {
+            Ok(commitment) => {
+                let mut result = vec![RESULT_OK];
+                result.extend(commitment);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    /// Serialize the ring params snapshot.
+    ///
+    /// `commitment` may be empty, in which case no verifier key is included.
+    pub fn serialize_ring_params(ring_size: u32, commitment: &[u8]) -> Vec<u8> {
+        let Ok(ring_size) = RingSize::from_key_count(ring_size as usize) else {
+            return vec![RESULT_ERR];
+        };
+        let commitment = if commitment.is_empty() {
+            None
+        } else {
+            match RingCommitment::deserialize_compressed_unchecked(commitment) {
+                Ok(commitment) => Some(commitment),
+                Err(_) => return vec![RESULT_ERR],
+            }
+        };
+
+        match super::serialize_ring_params(ring_size, commitment) {
+            Ok(snapshot) => {
+                let mut result = vec![RESULT_OK];
+                result.extend(snapshot);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    pub fn load_ring_params(snapshot: &[u8]) -> Vec<u8> {
+        match super::load_ring_params(snapshot) {
+            Ok(_) => vec![RESULT_OK],
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    pub fn init_pcs_params(bytes: &[u8]) -> Vec<u8> {
+        match super::init_pcs_params(bytes) {
+            Ok(()) => vec![RESULT_OK],
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    fn encode_key_handle(handle: Result<KeyHandle, Error>) -> Vec<u8> {
+        match handle {
+            Ok(handle) => {
+                let mut result = vec![RESULT_OK];
+                result.extend_from_slice(&handle.to_le_bytes());
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    /// Import a secret key from a seed. Returns `status || key handle (u32 LE)`.
+    pub fn import_secret_seed(seed: &[u8]) -> Vec<u8> {
+        encode_key_handle(Ok(super::import_secret_seed(seed)))
+    }
+
+    /// Import a raw secret scalar. Returns `status || key handle (u32 LE)`.
+    pub fn import_secret_scalar(scalar: &[u8]) -> Vec<u8> {
+        encode_key_handle(super::import_secret_scalar(scalar))
+    }
+
+    pub fn export_secret_scalar(key: u32) -> Vec<u8> {
+        match super::export_secret_scalar(key) {
+            Ok(scalar) => {
+                let mut result = Vec::with_capacity(1 + SECRET_SCALAR_SIZE);
+                result.push(RESULT_OK);
+                result.extend_from_slice(&scalar[..]);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    pub fn secret_key_public(key: u32) -> Vec<u8> {
+        match super::secret_key_public(key) {
+            Ok(public_key) => {
+                let mut result = vec![RESULT_OK];
+                result.extend(public_key);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    pub fn release_secret_key(key: u32) -> Vec<u8> {
+        if super::release_secret_key(key) {
+            vec![RESULT_OK]
+        } else {
+            vec![RESULT_ERR]
+        }
+    }
+
+    /// Import the bandersnatch keys of a keystore file.
+    ///
+    /// Returns `status || (key handle (u32 LE) || public key (32 bytes))*`.
+    pub fn import_keystore(file: &[u8], passphrase: &[u8]) -> Vec<u8> {
+        let imported = super::import_keystore(file, passphrase).and_then(|handles| {
+            let mut result = vec![RESULT_OK];
+            for handle in handles {
+                result.extend_from_slice(&handle.to_le_bytes());
+                result.extend(super::secret_key_public(handle)?);
+            }
+            Ok(result)
+        });
+        imported.unwrap_or_else(|_| vec![RESULT_ERR])
+    }
+
+    /// Seal keys (concatenated u32 LE handles) into a keystore file.
+    ///
+    /// Returns `status || keystore file`.
+    pub fn export_keystore(keys: &[u8], passphrase: &[u8]) -> Vec<u8> {
+        if !keys.len().is_multiple_of(4) {
+            return vec![RESULT_ERR];
+        }
+        let handles: Vec<KeyHandle> = keys
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        match super::export_keystore(&handles, passphrase, KdfParams::default()) {
+            Ok(file) => {
+                let mut result = vec![RESULT_OK];
+                result.extend(file);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    pub fn derive_public_key(seed: &[u8]) -> Vec<u8> {
+        match derive_public_key_from_seed(seed) {
+            Ok(key) => {
+                let mut result = vec![RESULT_OK];
+                result.extend(key);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    /// Generate the header seal and entropy source signature.
+    ///
+    /// Returns `status || seal (96 bytes) || entropy (96 bytes)`, zeroed on error.
+    pub fn generate_header_seals(
+        secret_seed: &[u8],
+        seal_payload: &[u8],
+        unsealed_header: &[u8],
+        entropy_prefix: &[u8],
+    ) -> Vec<u8> {
+        encode_header_seals(super::generate_header_seals(
+            secret_seed,
+            seal_payload,
+            unsealed_header,
+            entropy_prefix,
+        ))
+    }
+
+    pub fn generate_header_seals_with_key(
+        key: u32,
+        seal_payload: &[u8],
+        unsealed_header: &[u8],
+        entropy_prefix: &[u8],
+    ) -> Vec<u8> {
+        encode_header_seals(super::generate_header_seals_with_key(
+            key,
+            seal_payload,
+            unsealed_header,
+            entropy_prefix,
+        ))
     }
 
-    pub fn derive_public_key(seed: &[u8]) -> Vec<u8> {
-        match derive_public_key_from_seed(seed) {
-            Ok(key) => {
-                let mut result = vec![RESULT_OK];
-                result.extend(key);
+    fn encode_header_seals(seals: Result<(Vec<u8>, Vec<u8>), Error>) -> Vec<u8> {
+        match seals {
+            Ok((seal, entropy)) => {
+                let mut result = Vec::with_capacity(1 + 2 * IETF_SIGNATURE_SIZE);
+                result.push(RESULT_OK);
+                result.extend_from_slice(&seal);
+                result.extend_from_slice(&entropy);
+                result
+            }
+            Err(_) => {
+                let mut result = vec![RESULT_ERR];
+                result.extend([0u8; 2 * IETF_SIGNATURE_SIZE]);
                 result
             }
-            Err(_) => vec![RESULT_ERR],
         }
     }
 
@@ -561,13 +1854,70 @@ pub mod ffi {
         }
     }
 
+    /// Verify the seals of many headers (see `parse_header_seals` for the layout).
+    ///
+    /// Returns `(status || seal hash || entropy hash)*`, zeroed on error, or
+    /// just `[RESULT_ERR]` if the layout is malformed.
+    pub fn batch_verify_header_seals(headers_data: &[u8]) -> Vec<u8> {
+        let Ok(headers) = parse_header_seals(headers_data) else {
+            return vec![RESULT_ERR];
+        };
+        batch_verify_header_seals_impl(&headers)
+            .into_iter()
+            .fold(Vec::new(), |mut acc, result| {
+                match result {
+                    Ok((seal, entropy)) => {
+                        acc.push(RESULT_OK);
+                        acc.extend_from_slice(&seal);
+                        acc.extend_from_slice(&entropy);
+                    }
+                    Err(_) => {
+                        acc.push(RESULT_ERR);
+                        acc.extend([0u8; 64]);
+                    }
+                }
+                acc
+            })
+    }
+
+    /// Verify many IETF VRF signatures (see `parse_ietf_signatures` for the layout).
+    ///
+    /// Returns `(status || output hash)*`, zeroed on error, or just
+    /// `[RESULT_ERR]` if the layout is malformed.
+    pub fn batch_ietf_vrf_verify(signatures_data: &[u8]) -> Vec<u8> {
+        let Ok(signatures) = parse_ietf_signatures(signatures_data) else {
+            return vec![RESULT_ERR];
+        };
+        Verifier::batch_ietf_vrf_verify(&signatures)
+            .into_iter()
+            .fold(Vec::new(), |mut acc, result| {
+                match result {
+                    Ok(hash) => {
+                        acc.push(RESULT_OK);
+                        acc.extend_from_slice(&hash);
+                    }
+                    Err(_) => {
+                        acc.push(RESULT_ERR);
+                        acc.extend([0u8; 32]);
+                    }
+                }
+                acc
+            })
+    }
+
     pub fn verify_seal(
         signer_key: &[u8],
         seal_data: &[u8],
         payload: &[u8],
         aux_data: &[u8],
     ) -> Vec<u8> {
-        match verify_seal_impl(signer_key, seal_data, payload, aux_data) {
+        match verify_seal_impl(
+            signer_key,
+            seal_data,
+            payload,
+            aux_data,
+            VRF_OUTPUT_HASH_SIZE,
+        ) {
             Ok(entropy) => {
                 let mut result = vec![RESULT_OK];
                 result.extend(entropy);
@@ -582,7 +1932,15 @@ pub mod ffi {
     }
 
     pub fn generate_seal(secret_seed: &[u8], input: &[u8], aux_data: &[u8]) -> Vec<u8> {
-        match generate_ietf_seal(secret_seed, input, aux_data) {
+        encode_seal(generate_ietf_seal(secret_seed, input, aux_data))
+    }
+
+    pub fn generate_seal_with_key(key: u32, input: &[u8], aux_data: &[u8]) -> Vec<u8> {
+        encode_seal(generate_ietf_seal_with_key(key, input, aux_data))
+    }
+
+    fn encode_seal(seal: Result<Vec<u8>, Error>) -> Vec<u8> {
+        match seal {
             Ok(seal) => {
                 let mut result = vec![RESULT_OK];
                 result.extend(seal);
@@ -592,8 +1950,125 @@ pub mod ffi {
         }
     }
 
+    /// Generate a Pedersen VRF signature.
+    ///
+    /// Returns `status || signature (192B) || blinding factor (32B)`.
+    pub fn generate_pedersen_vrf(secret_seed: &[u8], input: &[u8], aux_data: &[u8]) -> Vec<u8> {
+        encode_pedersen_vrf(super::generate_pedersen_vrf(secret_seed, input, aux_data))
+    }
+
+    pub fn generate_pedersen_vrf_with_key(key: u32, input: &[u8], aux_data: &[u8]) -> Vec<u8> {
+        encode_pedersen_vrf(super::generate_pedersen_vrf_with_key(key, input, aux_data))
+    }
+
+    fn encode_pedersen_vrf(
+        signature: Result<(Vec<u8>, [u8; PEDERSEN_BLINDING_SIZE]), Error>,
+    ) -> Vec<u8> {
+        match signature {
+            Ok((signature, blinding)) => {
+                let mut result = vec![RESULT_OK];
+                result.extend(signature);
+                result.extend_from_slice(&blinding);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    /// Verify a Pedersen VRF signature.
+    ///
+    /// Returns `status || VRF output hash (32B) || blinded key commitment (32B)`,
+    /// zero-filled on failure.
+    pub fn verify_pedersen_vrf(input: &[u8], aux_data: &[u8], signature: &[u8]) -> Vec<u8> {
+        match Verifier::pedersen_vrf_verify(input, aux_data, signature) {
+            Ok((output_hash, key_commitment)) => {
+                let mut result = vec![RESULT_OK];
+                result.extend_from_slice(&output_hash);
+                result.extend_from_slice(&key_commitment);
+                result
+            }
+            Err(_) => {
+                let mut result = vec![RESULT_ERR];
+                result.extend([0u8; 32 + PEDERSEN_BLINDING_SIZE]);
+                result
+            }
+        }
+    }
+
+    pub fn pedersen_key_commitment(public_key: &[u8], blinding: &[u8]) -> Vec<u8> {
+        match super::pedersen_key_commitment(public_key, blinding) {
+            Ok(commitment) => {
+                let mut result = vec![RESULT_OK];
+                result.extend_from_slice(&commitment);
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
     pub fn vrf_output_hash(secret_seed: &[u8], input: &[u8]) -> Vec<u8> {
-        match compute_vrf_output_hash(secret_seed, input) {
+        encode_vrf_output_hash(compute_vrf_output_hash(
+            secret_seed,
+            input,
+            VRF_OUTPUT_HASH_SIZE,
+        ))
+    }
+
+    pub fn vrf_output_hash_with_key(key: u32, input: &[u8]) -> Vec<u8> {
+        encode_vrf_output_hash(compute_vrf_output_hash_with_key(
+            key,
+            input,
+            VRF_OUTPUT_HASH_SIZE,
+        ))
+    }
+
+    /// VRF output hash of an already verified IETF or ring signature.
+    ///
+    /// Returns `status || hash (32 bytes)`.
+    pub fn vrf_output_from_signature(signature: &[u8]) -> Vec<u8> {
+        encode_vrf_output_hash(crate::vrf_output_from_signature(
+            signature,
+            VRF_OUTPUT_HASH_SIZE,
+        ))
+    }
+
+    /// Full VRF output hash of an already verified IETF or ring signature.
+    ///
+    /// Returns `status || hash (64 bytes)`.
+    pub fn vrf_output_full_hash_from_signature(signature: &[u8]) -> Vec<u8> {
+        encode_vrf_output_hash(crate::vrf_output_from_signature(
+            signature,
+            MAX_VRF_OUTPUT_HASH_SIZE,
+        ))
+    }
+
+    /// VRF output hashes of consecutive inputs.
+    ///
+    /// Returns `(status || hash (32 bytes, zeroed on error))*`, one per input.
+    pub fn vrf_output_hashes(
+        secret_seed: &[u8],
+        inputs_data: &[u8],
+        vrf_input_data_len: u32,
+    ) -> Vec<u8> {
+        compute_vrf_output_hashes(secret_seed, inputs_data, vrf_input_data_len as usize)
+            .into_iter()
+            .fold(Vec::new(), |mut acc, hash| {
+                match hash {
+                    Ok(hash) => {
+                        acc.push(RESULT_OK);
+                        acc.extend_from_slice(&hash);
+                    }
+                    Err(_) => {
+                        acc.push(RESULT_ERR);
+                        acc.extend([0u8; 32]);
+                    }
+                }
+                acc
+            })
+    }
+
+    fn encode_vrf_output_hash(hash: Result<Vec<u8>, Error>) -> Vec<u8> {
+        match hash {
             Ok(hash) => {
                 let mut result = vec![RESULT_OK];
                 result.extend_from_slice(&hash);
@@ -610,6 +2085,10 @@ pub mod ffi {
                     acc.push(RESULT_OK);
                     acc.extend_from_slice(&signature);
                 }
+                Err(Error::OutputAboveThreshold) => {
+                    acc.push(RESULT_SKIPPED);
+                    acc.extend([0u8; RING_SIGNATURE_SIZE]);
+                }
                 Err(_) => {
                     acc.push(RESULT_ERR);
                     acc.extend([0u8; RING_SIGNATURE_SIZE]);
@@ -620,7 +2099,7 @@ pub mod ffi {
     }
 
     fn decode_prover_key_indices(prover_key_indices: &[u8]) -> Result<Vec<usize>, Error> {
-        if prover_key_indices.len() % 4 != 0 {
+        if !prover_key_indices.len().is_multiple_of(4) {
             return Err(Error::InvalidSignature);
         }
 
@@ -637,6 +2116,23 @@ pub mod ffi {
         prover_key_index: u32,
         secret_seed: &[u8],
         vrf_input_data: &[u8],
+    ) -> Vec<u8> {
+        generate_ring_vrf_with_aux(
+            ring_keys,
+            prover_key_index,
+            secret_seed,
+            vrf_input_data,
+            &[],
+        )
+    }
+
+    /// Same as [`generate_ring_vrf`], binding `aux_data` into the proof.
+    pub fn generate_ring_vrf_with_aux(
+        ring_keys: &[u8],
+        prover_key_index: u32,
+        secret_seed: &[u8],
+        vrf_input_data: &[u8],
+        aux_data: &[u8],
     ) -> Vec<u8> {
         let public_keys: Vec<_> = ring_keys
             .chunks(PUBLIC_KEY_SIZE)
@@ -648,17 +2144,74 @@ pub mod ffi {
             prover_key_index as usize,
             secret_seed,
             vrf_input_data,
+            aux_data,
         );
 
         encode_ring_vrf_generation_results(vec![result])
     }
 
+    /// Position of the secret seed's public key in the ring.
+    ///
+    /// Returns `status || index (u32 LE)`.
+    pub fn locate_prover_key_index(ring_keys: &[u8], secret_seed: &[u8]) -> Vec<u8> {
+        let public_keys: Vec<_> = ring_keys
+            .chunks(PUBLIC_KEY_SIZE)
+            .map(deserialize_public_key)
+            .collect();
+
+        match crate::locate_prover_key_index(&public_keys, secret_seed) {
+            Ok(index) => {
+                let mut result = vec![RESULT_OK];
+                result.extend_from_slice(&(index as u32).to_le_bytes());
+                result
+            }
+            Err(_) => vec![RESULT_ERR],
+        }
+    }
+
+    /// Like `generate_ring_vrf`, with the prover's index found from its secret seed.
+    pub fn generate_ring_vrf_for_secret(
+        ring_keys: &[u8],
+        secret_seed: &[u8],
+        vrf_input_data: &[u8],
+        aux_data: &[u8],
+    ) -> Vec<u8> {
+        let public_keys: Vec<_> = ring_keys
+            .chunks(PUBLIC_KEY_SIZE)
+            .map(deserialize_public_key)
+            .collect();
+
+        let result =
+            generate_ring_vrf_for_secret_impl(&public_keys, secret_seed, vrf_input_data, aux_data);
+
+        encode_ring_vrf_generation_results(vec![result])
+    }
+
     pub fn batch_generate_ring_vrf(
         ring_keys: &[u8],
         prover_key_index: u32,
         secret_seed: &[u8],
         inputs_data: &[u8],
         vrf_input_data_len: u32,
+    ) -> Vec<u8> {
+        batch_generate_ring_vrf_with_aux(
+            ring_keys,
+            prover_key_index,
+            secret_seed,
+            inputs_data,
+            vrf_input_data_len,
+            &[],
+        )
+    }
+
+    /// Same as [`batch_generate_ring_vrf`], binding `aux_data` into every proof.
+    pub fn batch_generate_ring_vrf_with_aux(
+        ring_keys: &[u8],
+        prover_key_index: u32,
+        secret_seed: &[u8],
+        inputs_data: &[u8],
+        vrf_input_data_len: u32,
+        aux_data: &[u8],
     ) -> Vec<u8> {
         let public_keys: Vec<_> = ring_keys
             .chunks(PUBLIC_KEY_SIZE)
@@ -671,6 +2224,90 @@ pub mod ffi {
             secret_seed,
             inputs_data,
             vrf_input_data_len as usize,
+            aux_data,
+        );
+
+        encode_ring_vrf_generation_results(results)
+    }
+
+    /// Same as [`batch_generate_ring_vrf`], but only proving inputs whose
+    /// output hash is below `threshold` (32 bytes).
+    ///
+    /// Skipped inputs get the `RESULT_SKIPPED` (`0x02`) status. A threshold of
+    /// a wrong length gives just `[RESULT_ERR]`.
+    pub fn batch_generate_ring_vrf_below_threshold(
+        ring_keys: &[u8],
+        prover_key_index: u32,
+        secret_seed: &[u8],
+        inputs_data: &[u8],
+        vrf_input_data_len: u32,
+        threshold: &[u8],
+        aux_data: &[u8],
+    ) -> Vec<u8> {
+        let Ok(threshold) = <&[u8; 32]>::try_from(threshold) else {
+            return vec![RESULT_ERR];
+        };
+        let public_keys: Vec<_> = ring_keys
+            .chunks(PUBLIC_KEY_SIZE)
+            .map(deserialize_public_key)
+            .collect();
+
+        let results = batch_generate_ring_vrf_below_threshold_impl(
+            &public_keys,
+            prover_key_index as usize,
+            secret_seed,
+            inputs_data,
+            vrf_input_data_len as usize,
+            threshold,
+            aux_data,
+        );
+
+        encode_ring_vrf_generation_results(results)
+    }
+
+    pub fn generate_ring_vrf_with_key(
+        ring_keys: &[u8],
+        prover_key_index: u32,
+        key: u32,
+        vrf_input_data: &[u8],
+        aux_data: &[u8],
+    ) -> Vec<u8> {
+        let public_keys: Vec<_> = ring_keys
+            .chunks(PUBLIC_KEY_SIZE)
+            .map(deserialize_public_key)
+            .collect();
+
+        let result = generate_ring_vrf_with_key_impl(
+            &public_keys,
+            prover_key_index as usize,
+            key,
+            vrf_input_data,
+            aux_data,
+        );
+
+        encode_ring_vrf_generation_results(vec![result])
+    }
+
+    pub fn batch_generate_ring_vrf_with_key(
+        ring_keys: &[u8],
+        prover_key_index: u32,
+        key: u32,
+        inputs_data: &[u8],
+        vrf_input_data_len: u32,
+        aux_data: &[u8],
+    ) -> Vec<u8> {
+        let public_keys: Vec<_> = ring_keys
+            .chunks(PUBLIC_KEY_SIZE)
+            .map(deserialize_public_key)
+            .collect();
+
+        let results = batch_generate_ring_vrf_with_key_impl(
+            &public_keys,
+            prover_key_index as usize,
+            key,
+            inputs_data,
+            vrf_input_data_len as usize,
+            aux_data,
         );
 
         encode_ring_vrf_generation_results(results)
@@ -683,9 +2320,33 @@ pub mod ffi {
         secret_seed_data_len: u32,
         inputs_data: &[u8],
         vrf_input_data_len: u32,
+    ) -> Vec<u8> {
+        batch_generate_ring_vrf_for_validators_with_aux(
+            ring_keys,
+            prover_key_indices,
+            secret_seeds_data,
+            secret_seed_data_len,
+            inputs_data,
+            vrf_input_data_len,
+            &[],
+        )
+    }
+
+    /// Same as [`batch_generate_ring_vrf_for_validators`], binding `aux_data`
+    /// into every proof.
+    pub fn batch_generate_ring_vrf_for_validators_with_aux(
+        ring_keys: &[u8],
+        prover_key_indices: &[u8],
+        secret_seeds_data: &[u8],
+        secret_seed_data_len: u32,
+        inputs_data: &[u8],
+        vrf_input_data_len: u32,
+        aux_data: &[u8],
     ) -> Vec<u8> {
         let secret_seed_data_len = secret_seed_data_len as usize;
-        if secret_seed_data_len == 0 || secret_seeds_data.len() % secret_seed_data_len != 0 {
+        if secret_seed_data_len == 0
+            || !secret_seeds_data.len().is_multiple_of(secret_seed_data_len)
+        {
             return vec![RESULT_ERR];
         }
 
@@ -710,6 +2371,7 @@ pub mod ffi {
             &secret_seeds,
             inputs_data,
             vrf_input_data_len as usize,
+            aux_data,
         ) {
             Ok(results) => results,
             Err(_) => return vec![RESULT_ERR],
@@ -737,16 +2399,58 @@ pub mod ffi {
         tickets_data: &[u8],
         vrf_input_data_len: u32,
     ) -> Vec<u8> {
-        let ring_size = RingSize::from_size(ring_size as usize);
         let chunk_size = vrf_input_data_len as usize + RING_SIGNATURE_SIZE;
         let num_tickets = tickets_data.len() / chunk_size;
 
-        match batch_verify_tickets_impl(
-            ring_size,
-            commitment,
-            tickets_data,
-            vrf_input_data_len as usize,
-        ) {
+        match RingSize::from_key_count(ring_size as usize).and_then(|ring_size| {
+            batch_verify_tickets_impl(
+                ring_size,
+                commitment,
+                tickets_data,
+                vrf_input_data_len as usize,
+            )
+        }) {
+            Ok(entropies) => {
+                let mut result = Vec::with_capacity(1 + entropies.len() * 32);
+                result.push(RESULT_OK);
+                for entropy in entropies {
+                    result.extend_from_slice(&entropy);
+                }
+                result
+            }
+            Err(_) => {
+                let mut result = vec![RESULT_ERR];
+                result.resize(1 + num_tickets * 32, 0);
+                result
+            }
+        }
+    }
+
+    /// Batch verify tickets with per-ticket aux data and encode the result.
+    ///
+    /// Tickets are laid out as `signature || vrf_input || aux_data_len (u32 LE) || aux_data`.
+    /// The response has the same shape as for [`batch_verify_tickets`]. If the
+    /// tickets layout itself is malformed, the number of tickets is unknown and
+    /// the response is just `[RESULT_ERR]`.
+    pub fn batch_verify_tickets_with_aux(
+        ring_size: u32,
+        commitment: &[u8],
+        tickets_data: &[u8],
+        vrf_input_data_len: u32,
+    ) -> Vec<u8> {
+        let Ok(tickets) = parse_tickets_with_aux(tickets_data, vrf_input_data_len as usize) else {
+            return vec![RESULT_ERR];
+        };
+        let num_tickets = tickets.len();
+
+        match RingSize::from_key_count(ring_size as usize).and_then(|ring_size| {
+            batch_verify_ticket_data(
+                &RING_CACHE,
+                ring_size,
+                commitment,
+                tickets.into_iter().map(Ok),
+            )
+        }) {
             Ok(entropies) => {
                 let mut result = Vec::with_capacity(1 + entropies.len() * 32);
                 result.push(RESULT_OK);
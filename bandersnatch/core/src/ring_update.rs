@@ -0,0 +1,90 @@
+//! Ring commitment updates across epochs.
+//!
+//! The ring commitment commits to the coordinates of the ring keys with the
+//! SRS in Lagrangian form: for `Li = L_i(t)G`, the x coordinates `x1, ..., xn`
+//! are committed as `x1L1 + ... + xnLn`, and likewise the y coordinates.
+//! Replacing the key at index `i` thus only adds `(x' - x)Li` and `(y' - y)Li`
+//! to the previous commitment, which is much cheaper than committing to all
+//! the keys again when only a few validators changed.
+//!
+//! Deriving the Lagrangian SRS is itself as expensive as a full commitment, so
+//! it is done once per ring size and kept for the lifetime of the process.
+
+use std::sync::OnceLock;
+
+use ark_vrf::reexports::ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_vrf::reexports::ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_vrf::ring::{G1Affine, RingBuilderPcsParams};
+
+use crate::bandersnatch::{BandersnatchSha512Ell2, Public};
+use crate::{Error, RingCommitment, RingSize, ring_proof_params};
+
+type LagrangianSrs = RingBuilderPcsParams<BandersnatchSha512Ell2>;
+type G1Projective = <G1Affine<BandersnatchSha512Ell2> as AffineRepr>::Group;
+
+/// Storage of the Lagrangian SRS derived for given ring size.
+fn lagrangian_srs_slot(ring_size: RingSize) -> &'static OnceLock<LagrangianSrs> {
+    static SRS_TINY: OnceLock<LagrangianSrs> = OnceLock::new();
+    static SRS_FULL: OnceLock<LagrangianSrs> = OnceLock::new();
+
+    match ring_size {
+        RingSize::Tiny => &SRS_TINY,
+        RingSize::Full => &SRS_FULL,
+    }
+}
+
+fn lagrangian_srs(ring_size: RingSize) -> Result<&'static LagrangianSrs, Error> {
+    let srs = lagrangian_srs_slot(ring_size);
+
+    if let Some(srs) = srs.get() {
+        return Ok(srs);
+    }
+
+    let (_, lagrangian) = ring_proof_params(ring_size)?.verifier_key_builder();
+    // Another thread might have been faster, both results are the same.
+    Ok(srs.get_or_init(|| lagrangian))
+}
+
+/// Serialized commitment to `previous_keys` with the keys at the given
+/// indices replaced, computed from `previous_commitment`.
+pub(crate) fn update_ring_commitment(
+    previous_keys: &[Public],
+    previous_commitment: &[u8],
+    replacements: &[(usize, Public)],
+    ring_size: RingSize,
+) -> Result<Vec<u8>, Error> {
+    let mut commitment = RingCommitment::deserialize_compressed_unchecked(previous_commitment)
+        .map_err(|_| Error::InvalidSignature)?;
+
+    let ring_keys = previous_keys
+        .len()
+        .min(ring_proof_params(ring_size)?.max_ring_size());
+    if replacements.iter().any(|(index, _)| *index >= ring_keys) {
+        return Err(Error::InvalidKeyIndex);
+    }
+    let srs = lagrangian_srs(ring_size)?;
+
+    let mut keys = previous_keys.to_vec();
+    let mut bases = Vec::with_capacity(replacements.len());
+    let (mut x_deltas, mut y_deltas) = (Vec::new(), Vec::new());
+    for (index, key) in replacements {
+        let previous = std::mem::replace(&mut keys[*index], *key);
+        bases.push(srs.0[*index]);
+        x_deltas.push(key.0.x - previous.0.x);
+        y_deltas.push(key.0.y - previous.0.y);
+    }
+
+    let [x, y] = &mut commitment.points;
+    let updated = G1Projective::normalize_batch(&[
+        x.0 + G1Projective::msm_unchecked(&bases, &x_deltas),
+        y.0 + G1Projective::msm_unchecked(&bases, &y_deltas),
+    ]);
+    x.0 = updated[0];
+    y.0 = updated[1];
+
+    let mut result = Vec::new();
+    commitment
+        .serialize_compressed(&mut result)
+        .map_err(|_| Error::InvalidSignature)?;
+    Ok(result)
+}
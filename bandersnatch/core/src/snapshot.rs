@@ -0,0 +1,142 @@
+//! Ring parameters snapshots.
+//!
+//! Deriving the ring proof parameters (and the verifier of the current ring)
+//! happens lazily on first use, which makes the first ticket verification after
+//! process start slow. Nodes can instead serialize the derived data into a
+//! snapshot, store it in a cache file and load it back on the next start.
+//!
+//! Snapshot layout:
+//!
+//! ```text
+//! magic (4B) || format version (1B) || ring size (u32 LE) || sha256(payload) (32B) || payload
+//! ```
+//!
+//! where `payload` is the uncompressed `RingProofParams` followed by an optional
+//! ring verifier key.
+//!
+//! The payload is deserialized without the (expensive) point validation, so a
+//! snapshot must only be loaded from a location as trusted as the binary itself.
+//! The hash only detects accidental corruption (e.g. a truncated write): anyone
+//! able to replace the file can recompute it.
+//!
+//! A snapshot is only loaded if it was derived from the PCS parameters in use,
+//! so that it agrees with the parameters derived for the other ring size.
+
+use ark_vrf::reexports::ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+
+use crate::bandersnatch::{BandersnatchSha512Ell2, RingProofParams, RingVerifierKey};
+use crate::{
+    Error, RingCommitment, RingSize, cache, pcs_params, ring_params_slot, ring_proof_params,
+};
+
+const MAGIC: &[u8; 4] = b"BSRP";
+
+/// Version of the snapshot format (bumped whenever the payload changes).
+const FORMAT_VERSION: u8 = 1;
+
+const HEADER_SIZE: usize = MAGIC.len() + 1 + 4 + 32;
+
+/// Serialize the ring proof parameters for given ring size.
+///
+/// If `commitment` is given, the verifier key of that ring is included as well,
+/// so that loading the snapshot also prepares a verifier for that ring.
+pub fn serialize_ring_params(
+    ring_size: RingSize,
+    commitment: Option<RingCommitment>,
+) -> Result<Vec<u8>, Error> {
+    let ring_params = ring_proof_params(ring_size)?;
+    let verifier_key = commitment.map(|c| ring_params.verifier_key_from_commitment(c));
+
+    let mut payload = Vec::new();
+    ring_params
+        .serialize_uncompressed(&mut payload)
+        .map_err(|_| Error::InvalidRingParams)?;
+    verifier_key
+        .serialize_uncompressed(&mut payload)
+        .map_err(|_| Error::InvalidRingParams)?;
+
+    let mut snapshot = Vec::with_capacity(HEADER_SIZE + payload.len());
+    snapshot.extend_from_slice(MAGIC);
+    snapshot.push(FORMAT_VERSION);
+    snapshot.extend_from_slice(&(ring_size.size() as u32).to_le_bytes());
+    snapshot.extend_from_slice(&Sha256::digest(&payload));
+    snapshot.extend_from_slice(&payload);
+    Ok(snapshot)
+}
+
+/// Parse and check a snapshot created by [`serialize_ring_params`].
+pub(crate) fn parse_ring_params(
+    snapshot: &[u8],
+) -> Result<(RingSize, RingProofParams, Option<RingVerifierKey>), Error> {
+    if snapshot.len() < HEADER_SIZE {
+        return Err(Error::InvalidRingParams);
+    }
+    let (magic, rest) = snapshot.split_at(MAGIC.len());
+    let (version, rest) = rest.split_at(1);
+    let (size, rest) = rest.split_at(4);
+    let (hash, payload) = rest.split_at(32);
+
+    if magic != MAGIC || version[0] != FORMAT_VERSION {
+        return Err(Error::InvalidRingParams);
+    }
+    let size = u32::from_le_bytes(size.try_into().expect("split at 4 bytes")) as usize;
+    let ring_size = RingSize::from_key_count(size).map_err(|_| Error::InvalidRingParams)?;
+    if Sha256::digest(payload).as_slice() != hash {
+        return Err(Error::InvalidRingParams);
+    }
+
+    // Points are not validated, see the module documentation.
+    let mut reader = payload;
+    let ring_params = RingProofParams::deserialize_uncompressed_unchecked(&mut reader)
+        .map_err(|_| Error::InvalidRingParams)?;
+    let verifier_key = Option::<RingVerifierKey>::deserialize_uncompressed_unchecked(&mut reader)
+        .map_err(|_| Error::InvalidRingParams)?;
+    if !reader.is_empty() || ring_params.max_ring_size() < ring_size.size() {
+        return Err(Error::InvalidRingParams);
+    }
+
+    Ok((ring_size, ring_params, verifier_key))
+}
+
+/// Check that `ring_params` hold the PCS parameters in use, as truncated by
+/// [`RingProofParams::from_pcs_params`].
+fn derived_from_pcs_params(
+    ring_size: RingSize,
+    ring_params: &RingProofParams,
+) -> Result<bool, Error> {
+    let pcs_params = pcs_params()?;
+    let g1_len = ark_vrf::ring::pcs_domain_size::<BandersnatchSha512Ell2>(ring_size.size());
+    // Only the first two powers in G2 are used.
+    Ok(
+        pcs_params.powers_in_g1.get(..g1_len) == Some(&ring_params.pcs.powers_in_g1[..])
+            && pcs_params.powers_in_g2.get(..2) == Some(&ring_params.pcs.powers_in_g2[..]),
+    )
+}
+
+/// Load ring proof parameters from a snapshot created by [`serialize_ring_params`].
+///
+/// The snapshot must come from a trusted location, as its content is not
+/// validated beyond a corruption check. It is rejected if it was derived from
+/// other PCS parameters than the ones in use (supplied with
+/// [`init_pcs_params`](crate::init_pcs_params), or the embedded ones), which
+/// must be available.
+///
+/// Must be called before the parameters for that ring size are first used.
+/// On success returns the ring size of the loaded parameters.
+pub fn load_ring_params(snapshot: &[u8]) -> Result<RingSize, Error> {
+    let (ring_size, ring_params, verifier_key) = parse_ring_params(snapshot)?;
+    if !derived_from_pcs_params(ring_size, &ring_params)? {
+        return Err(Error::InvalidRingParams);
+    }
+
+    ring_params_slot(ring_size)
+        .set(ring_params)
+        .map_err(|_| Error::RingParamsAlreadySet)?;
+
+    if let Some(verifier_key) = verifier_key {
+        cache::RING_CACHE.insert_ring_verifier_key(ring_size, verifier_key)?;
+    }
+
+    Ok(ring_size)
+}
@@ -1,14 +1,32 @@
+/// Supply the PCS params from the data file when no SRS is embedded.
+///
+/// Must be called by tests using the ring params; does nothing when the
+/// embedded SRS is available.
+#[cfg(test)]
+pub(crate) fn init_test_pcs_params() {
+    #[cfg(not(feature = "embedded-srs"))]
+    {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let buf = include_bytes!("../../data/zcash-srs-2-11-uncompressed.bin");
+            crate::init_pcs_params(buf).expect("PCS params are only set here");
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        RING_SIGNATURE_SIZE, RingSize, batch_generate_ring_vrf_for_validators_impl,
-        batch_generate_ring_vrf_impl, compute_ring_commitment, compute_vrf_output_hash,
-        derive_public_key_from_seed, deserialize_public_key, generate_ietf_seal,
-        generate_ring_vrf_impl, verify_header_seals_impl, verify_seal_impl,
+        RING_SIGNATURE_SIZE, RingSize, VRF_OUTPUT_HASH_SIZE,
+        batch_generate_ring_vrf_for_validators_impl, batch_generate_ring_vrf_impl,
+        compute_ring_commitment, compute_vrf_output_hash, derive_public_key_from_seed,
+        deserialize_public_key, generate_ietf_seal, generate_ring_vrf_impl,
+        verify_header_seals_impl, verify_seal_impl,
     };
 
     #[test]
     fn should_get_ring_commitment() {
+        super::init_test_pcs_params();
         let keys_str = "ff71c6c03ff88adb5ed52c9681de1629a54e702fc14729f6b50d2f0a76f185b3dee6d555b82024f1ccf8a1e37e60fa60fd40b1958c4bb3006af78647950e1b919326edb21e5541717fde24ec085000b28709847b8aab1ac51f84e94b37ca1b660746846d17469fb2f95ef365efcab9f4e22fa1feb53111c995376be8019981cc151e5c8fe2b9d8a606966a79edd2f9e5db47e83947ce368ccba53bf6ba20a40b2105650944fcd101621fd5bb3124c9fd191d114b7ad936c1d79d734f9f21392e";
         let keys = hex::decode(keys_str).unwrap();
 
@@ -83,7 +101,7 @@ mod tests {
         let input = b"example input";
         let aux_data = b"example aux data";
 
-        let id = verify_seal_impl(&pub_key, &seal, input, aux_data).unwrap();
+        let id = verify_seal_impl(&pub_key, &seal, input, aux_data, VRF_OUTPUT_HASH_SIZE).unwrap();
 
         assert_eq!(
             hex::encode(id),
@@ -100,7 +118,13 @@ mod tests {
         let input = b"example input";
         let aux_data = b"example aux data";
 
-        let result = verify_seal_impl(&incorrect_pub_key, &seal, input, aux_data);
+        let result = verify_seal_impl(
+            &incorrect_pub_key,
+            &seal,
+            input,
+            aux_data,
+            VRF_OUTPUT_HASH_SIZE,
+        );
         assert!(result.is_err());
     }
 
@@ -112,11 +136,12 @@ mod tests {
         let aux_data = b"test auxiliary data";
 
         let pub_key = derive_public_key_from_seed(&seed).unwrap();
-        let entropy = compute_vrf_output_hash(&seed, input).unwrap();
+        let entropy = output_hash(&seed, input);
         let seal = generate_ietf_seal(&seed, input, aux_data).unwrap();
-        let verify_result = verify_seal_impl(&pub_key, &seal, input, aux_data).unwrap();
+        let verify_result =
+            verify_seal_impl(&pub_key, &seal, input, aux_data, VRF_OUTPUT_HASH_SIZE).unwrap();
 
-        assert_eq!(entropy, verify_result);
+        assert_eq!(entropy, verify_result[..]);
     }
 
     #[test]
@@ -129,8 +154,14 @@ mod tests {
 
         let pub_key = derive_public_key_from_seed(&seed).unwrap();
         let seal_data = generate_ietf_seal(&seed, seal_payload, unsealed_header).unwrap();
-        let seal_output =
-            verify_seal_impl(&pub_key, &seal_data, seal_payload, unsealed_header).unwrap();
+        let seal_output = verify_seal_impl(
+            &pub_key,
+            &seal_data,
+            seal_payload,
+            unsealed_header,
+            VRF_OUTPUT_HASH_SIZE,
+        )
+        .unwrap();
 
         let mut entropy_payload = Vec::with_capacity(entropy_prefix.len() + seal_output.len());
         entropy_payload.extend_from_slice(entropy_prefix);
@@ -148,11 +179,17 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(returned_seal, seal_output);
+        assert_eq!(returned_seal, seal_output[..]);
 
-        let expected_entropy =
-            verify_seal_impl(&pub_key, &entropy_data, &entropy_payload, &[]).unwrap();
-        assert_eq!(returned_entropy, expected_entropy);
+        let expected_entropy = verify_seal_impl(
+            &pub_key,
+            &entropy_data,
+            &entropy_payload,
+            &[],
+            VRF_OUTPUT_HASH_SIZE,
+        )
+        .unwrap();
+        assert_eq!(returned_entropy, expected_entropy[..]);
     }
 
     #[test]
@@ -205,6 +242,14 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Helper: VRF output hash of the default size.
+    fn output_hash(seed: &[u8], input: &[u8]) -> [u8; 32] {
+        compute_vrf_output_hash(seed, input, VRF_OUTPUT_HASH_SIZE)
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
     /// Helper: create a ring of `size` keys, returning (seeds, public_keys).
     fn make_ring(size: usize) -> (Vec<Vec<u8>>, Vec<crate::bandersnatch::Public>) {
         let seeds: Vec<Vec<u8>> = (0..size).map(|i| i.to_le_bytes().to_vec()).collect();
@@ -220,6 +265,7 @@ mod tests {
 
     #[test]
     fn should_batch_generate_and_batch_verify() {
+        super::init_test_pcs_params();
         let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
         let prover_index = 1;
         let input_len = 36;
@@ -238,6 +284,7 @@ mod tests {
             &seeds[prover_index],
             &inputs_data,
             input_len,
+            &[],
         );
 
         assert_eq!(results.len(), num_inputs as usize);
@@ -263,13 +310,14 @@ mod tests {
         assert_eq!(verify_results.len(), num_inputs as usize);
         for (i, verified_hash) in verify_results.iter().enumerate() {
             let vrf_input = &inputs_data[i * input_len..(i + 1) * input_len];
-            let expected = compute_vrf_output_hash(&seeds[prover_index], vrf_input).unwrap();
+            let expected = output_hash(&seeds[prover_index], vrf_input);
             assert_eq!(*verified_hash, expected);
         }
     }
 
     #[test]
     fn should_generate_specific_ring_vrf_attempt() {
+        super::init_test_pcs_params();
         let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
         let prover_index = 1;
         let input_len = 36;
@@ -279,9 +327,14 @@ mod tests {
         vrf_input.extend_from_slice(&[0xCD; 32]);
         vrf_input.extend_from_slice(&attempt.to_le_bytes());
 
-        let signature =
-            generate_ring_vrf_impl(&public_keys, prover_index, &seeds[prover_index], &vrf_input)
-                .unwrap();
+        let signature = generate_ring_vrf_impl(
+            &public_keys,
+            prover_index,
+            &seeds[prover_index],
+            &vrf_input,
+            &[],
+        )
+        .unwrap();
 
         let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
         let mut verify_data = Vec::new();
@@ -296,12 +349,13 @@ mod tests {
         )
         .expect("single-attempt ticket should verify");
 
-        let expected = compute_vrf_output_hash(&seeds[prover_index], &vrf_input).unwrap();
+        let expected = output_hash(&seeds[prover_index], &vrf_input);
         assert_eq!(verify_results, vec![expected]);
     }
 
     #[test]
     fn should_batch_generate_for_multiple_validators() {
+        super::init_test_pcs_params();
         let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
         let prover_indices = vec![1usize, 3usize];
         let secret_seeds: Vec<&[u8]> = prover_indices
@@ -323,6 +377,7 @@ mod tests {
             &secret_seeds,
             &inputs_data,
             input_len,
+            &[],
         )
         .unwrap();
 
@@ -354,14 +409,78 @@ mod tests {
                 let result_offset = validator_offset * num_inputs as usize + input_offset;
                 let vrf_input =
                     &inputs_data[input_offset * input_len..(input_offset + 1) * input_len];
-                let expected = compute_vrf_output_hash(&seeds[prover_index], vrf_input).unwrap();
+                let expected = output_hash(&seeds[prover_index], vrf_input);
                 assert_eq!(verify_results[result_offset], expected);
             }
         }
     }
 
+    #[test]
+    fn should_keep_validator_major_order_with_invalid_validator() {
+        super::init_test_pcs_params();
+        let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
+        let prover_indices = vec![2usize, RingSize::Tiny.size(), 4usize];
+        let secret_seeds: Vec<&[u8]> = vec![&seeds[2], &seeds[0], &seeds[4]];
+        let input_len = 36;
+        let num_inputs = 2usize;
+
+        let mut inputs_data = Vec::new();
+        for attempt in 0..num_inputs as u32 {
+            inputs_data.extend_from_slice(&[0xCD; 32]);
+            inputs_data.extend_from_slice(&attempt.to_le_bytes());
+        }
+
+        let results = batch_generate_ring_vrf_for_validators_impl(
+            &public_keys,
+            &prover_indices,
+            &secret_seeds,
+            &inputs_data,
+            input_len,
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), prover_indices.len() * num_inputs);
+        assert!(results[0..2].iter().all(|r| r.is_ok()));
+        assert_eq!(
+            results[2..4],
+            [
+                Err(crate::Error::InvalidSignature),
+                Err(crate::Error::InvalidSignature)
+            ]
+        );
+        assert!(results[4..6].iter().all(|r| r.is_ok()));
+
+        let single =
+            batch_generate_ring_vrf_impl(&public_keys, 4, &seeds[4], &inputs_data, input_len, &[]);
+        let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        for (input_offset, result) in results[4..6].iter().enumerate() {
+            let mut verify_data = result.as_ref().unwrap().clone();
+            verify_data.extend_from_slice(
+                &inputs_data[input_offset * input_len..(input_offset + 1) * input_len],
+            );
+            let verified = crate::batch_verify_tickets_impl(
+                RingSize::Tiny,
+                &commitment_bytes,
+                &verify_data,
+                input_len,
+            )
+            .unwrap();
+            let expected = single[input_offset].as_ref().unwrap();
+            assert_eq!(&expected[..32], &result.as_ref().unwrap()[..32]);
+            assert_eq!(
+                verified[0],
+                output_hash(
+                    &seeds[4],
+                    &inputs_data[input_offset * input_len..(input_offset + 1) * input_len]
+                )
+            );
+        }
+    }
+
     #[test]
     fn should_encode_multi_validator_batch_generation_ffi_wire_format() {
+        super::init_test_pcs_params();
         const RESULT_OK: u8 = 0;
 
         let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
@@ -445,6 +564,7 @@ mod tests {
             &seeds[prover_index],
             &inputs_data,
             input_len,
+            &[],
         );
 
         let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
@@ -461,6 +581,7 @@ mod tests {
 
     #[test]
     fn should_fail_whole_batch_when_one_ticket_is_invalid() {
+        super::init_test_pcs_params();
         let num_inputs = 3u32;
         let (commitment_bytes, mut verify_data, input_len) = make_valid_batch(num_inputs);
 
@@ -483,6 +604,7 @@ mod tests {
 
     #[test]
     fn should_fail_batch_with_invalid_commitment() {
+        super::init_test_pcs_params();
         let (_commitment_bytes, verify_data, input_len) = make_valid_batch(2);
         let bad_commitment = [0xAB; 16];
 
@@ -498,6 +620,7 @@ mod tests {
 
     #[test]
     fn should_encode_batch_verify_ffi_wire_format() {
+        super::init_test_pcs_params();
         const RESULT_OK: u8 = 0;
         const RESULT_ERR: u8 = 1;
 
@@ -537,4 +660,1530 @@ mod tests {
         assert_eq!(err.len(), 1 + num_inputs as usize * 32);
         assert!(err[1..].iter().all(|&b| b == 0));
     }
+
+    fn cache_hash(n: u8) -> crate::cache::RingHash {
+        [n; 32]
+    }
+
+    #[test]
+    fn should_evict_least_recently_used_ring_cache_entry() {
+        let mut cache = crate::cache::RingCache::new(2);
+        cache.entry(RingSize::Tiny, Some(cache_hash(1)), Some(&[1]));
+        cache.entry(RingSize::Tiny, Some(cache_hash(2)), Some(&[2]));
+
+        // Touch the first entry, so that the second one gets evicted.
+        assert!(cache.by_keys(RingSize::Tiny, &cache_hash(1)).is_some());
+        cache.entry(RingSize::Tiny, Some(cache_hash(3)), Some(&[3]));
+
+        assert_eq!(cache.entries.len(), 2);
+        assert!(cache.by_keys(RingSize::Tiny, &cache_hash(2)).is_none());
+        assert!(cache.by_keys(RingSize::Tiny, &cache_hash(1)).is_some());
+        assert!(cache.by_keys(RingSize::Tiny, &cache_hash(3)).is_some());
+
+        cache.clear();
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn should_merge_ring_cache_entries_found_by_commitment() {
+        let mut cache = crate::cache::RingCache::new(2);
+        cache.entry(RingSize::Tiny, None, Some(&[1]));
+        cache.entry(RingSize::Tiny, Some(cache_hash(1)), Some(&[1]));
+
+        assert_eq!(cache.entries.len(), 1);
+        assert!(cache.by_keys(RingSize::Tiny, &cache_hash(1)).is_some());
+        assert!(cache.by_commitment(RingSize::Full, &[1]).is_none());
+    }
+
+    #[test]
+    fn should_return_same_results_with_cold_and_warm_ring_cache() {
+        super::init_test_pcs_params();
+        let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
+        let mut vrf_input = vec![0xAB; 32];
+        vrf_input.extend_from_slice(&0u32.to_le_bytes());
+
+        crate::clear_ring_cache();
+        let cold_commitment = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        let cold_signature =
+            generate_ring_vrf_impl(&public_keys, 2, &seeds[2], &vrf_input, &[]).unwrap();
+
+        let warm_commitment = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        let warm_signature =
+            generate_ring_vrf_impl(&public_keys, 2, &seeds[2], &vrf_input, &[]).unwrap();
+        assert_eq!(cold_commitment, warm_commitment);
+
+        use ark_vrf::reexports::ark_serialize::CanonicalDeserialize;
+        let commitment =
+            crate::RingCommitment::deserialize_compressed(&warm_commitment[..]).unwrap();
+        for signature in [&cold_signature, &warm_signature] {
+            let output = crate::Verifier::ring_vrf_verify(
+                RingSize::Tiny,
+                commitment.clone(),
+                &vrf_input,
+                &[],
+                signature,
+            )
+            .unwrap();
+            assert_eq!(output, output_hash(&seeds[2], &vrf_input));
+        }
+
+        // A cache of its own keeps the other tests from evicting the ring.
+        let cache = crate::cache::SharedRingCache::new(crate::RING_CACHE_CAPACITY);
+        for _ in 0..2 {
+            let tickets = [&cold_signature, &warm_signature].map(|signature| {
+                Ok(crate::TicketData {
+                    signature,
+                    vrf_input_data: &vrf_input,
+                    aux_data: &[],
+                })
+            });
+            let outputs = crate::batch_verify_ticket_data(
+                &cache,
+                RingSize::Tiny,
+                &warm_commitment,
+                tickets.into_iter(),
+            )
+            .unwrap();
+            assert_eq!(outputs, vec![output_hash(&seeds[2], &vrf_input); 2]);
+        }
+        // The second batch takes the verifier key built by the first one.
+        assert_eq!(cache.with(|cache| cache.verifier_key_builds), 1);
+    }
+
+    fn embedded_pcs_params() -> crate::bandersnatch::PcsParams {
+        use ark_vrf::reexports::ark_serialize::CanonicalDeserialize;
+        let buf = include_bytes!("../../data/zcash-srs-2-11-uncompressed.bin");
+        crate::bandersnatch::PcsParams::deserialize_uncompressed_unchecked(&mut &buf[..]).unwrap()
+    }
+
+    #[test]
+    fn should_parse_compressed_and_uncompressed_pcs_params() {
+        use ark_vrf::reexports::ark_serialize::CanonicalSerialize;
+        let params = embedded_pcs_params();
+
+        let mut uncompressed = Vec::new();
+        params.serialize_uncompressed(&mut uncompressed).unwrap();
+        let mut compressed = Vec::new();
+        params.serialize_compressed(&mut compressed).unwrap();
+        assert!(compressed.len() < uncompressed.len());
+
+        for bytes in [&uncompressed, &compressed] {
+            let parsed = crate::parse_pcs_params(bytes).unwrap();
+            assert_eq!(parsed.powers_in_g1, params.powers_in_g1);
+            assert_eq!(parsed.powers_in_g2, params.powers_in_g2);
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_pcs_params() {
+        use ark_vrf::reexports::ark_serialize::CanonicalSerialize;
+
+        assert_eq!(
+            crate::parse_pcs_params(&[0xAB; 1024]).err(),
+            Some(crate::Error::InvalidPcsParams)
+        );
+
+        // too small for the full ring.
+        let mut params = embedded_pcs_params();
+        params.powers_in_g1.truncate(1024);
+        let mut bytes = Vec::new();
+        params.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            crate::parse_pcs_params(&bytes).err(),
+            Some(crate::Error::InvalidPcsParams)
+        );
+
+        // trailing garbage.
+        let mut bytes = Vec::new();
+        embedded_pcs_params()
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        bytes.push(0);
+        assert_eq!(
+            crate::parse_pcs_params(&bytes).err(),
+            Some(crate::Error::InvalidPcsParams)
+        );
+    }
+
+    #[test]
+    fn should_not_replace_pcs_params_once_in_use() {
+        super::init_test_pcs_params();
+        crate::ring_proof_params(RingSize::Tiny).unwrap();
+
+        let buf = include_bytes!("../../data/zcash-srs-2-11-uncompressed.bin");
+        assert_eq!(
+            crate::init_pcs_params(buf),
+            Err(crate::Error::PcsParamsAlreadySet)
+        );
+        assert_eq!(crate::ffi::init_pcs_params(buf), vec![1]);
+    }
+
+    #[test]
+    #[cfg(not(feature = "embedded-srs"))]
+    fn should_use_supplied_pcs_params_without_embedded_srs() {
+        super::init_test_pcs_params();
+
+        let params = crate::pcs_params().unwrap();
+        assert_eq!(params.powers_in_g1, embedded_pcs_params().powers_in_g1);
+
+        let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
+        let vrf_input = [0xAB; 32];
+        let commitment = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        let signature =
+            generate_ring_vrf_impl(&public_keys, 1, &seeds[1], &vrf_input, &[]).unwrap();
+        let outputs = crate::batch_verify_tickets_impl(
+            RingSize::Tiny,
+            &commitment,
+            &[signature, vrf_input.to_vec()].concat(),
+            vrf_input.len(),
+        )
+        .unwrap();
+        assert_eq!(outputs, vec![output_hash(&seeds[1], &vrf_input)]);
+    }
+
+    #[test]
+    fn should_embed_same_srs_compressed_and_uncompressed() {
+        use ark_vrf::reexports::ark_serialize::CanonicalDeserialize;
+        let buf = include_bytes!("../../data/zcash-srs-2-11-compressed.bin");
+        let compressed =
+            crate::bandersnatch::PcsParams::deserialize_compressed_unchecked(&mut &buf[..])
+                .unwrap();
+        let uncompressed = embedded_pcs_params();
+
+        assert_eq!(compressed.powers_in_g1, uncompressed.powers_in_g1);
+        assert_eq!(compressed.powers_in_g2, uncompressed.powers_in_g2);
+    }
+
+    #[test]
+    fn should_serialize_and_parse_ring_params_snapshot() {
+        use ark_vrf::reexports::ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+        super::init_test_pcs_params();
+        let (_seeds, public_keys) = make_ring(RingSize::Tiny.size());
+        let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        let commitment =
+            crate::RingCommitment::deserialize_compressed(&commitment_bytes[..]).unwrap();
+
+        let snapshot =
+            crate::serialize_ring_params(RingSize::Tiny, Some(commitment.clone())).unwrap();
+        let (ring_size, ring_params, verifier_key) =
+            crate::snapshot::parse_ring_params(&snapshot).unwrap();
+
+        let expected = crate::ring_proof_params(RingSize::Tiny).unwrap();
+        assert_eq!(ring_size, RingSize::Tiny);
+        assert_eq!(ring_params.pcs.powers_in_g1, expected.pcs.powers_in_g1);
+        assert_eq!(ring_params.max_ring_size(), expected.max_ring_size());
+        let serialize = |vk: crate::bandersnatch::RingVerifierKey| {
+            let mut buf = Vec::new();
+            vk.serialize_uncompressed(&mut buf).unwrap();
+            buf
+        };
+        assert_eq!(
+            verifier_key.map(serialize),
+            Some(serialize(expected.verifier_key_from_commitment(commitment)))
+        );
+
+        // Parameters are already in use, so they can't be replaced.
+        assert_eq!(
+            crate::load_ring_params(&snapshot),
+            Err(crate::Error::RingParamsAlreadySet)
+        );
+    }
+
+    #[test]
+    fn should_reject_corrupted_ring_params_snapshot() {
+        super::init_test_pcs_params();
+        let snapshot = crate::serialize_ring_params(RingSize::Tiny, None).unwrap();
+
+        let mut corrupted = snapshot.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xFF;
+        assert_eq!(
+            crate::load_ring_params(&corrupted),
+            Err(crate::Error::InvalidRingParams)
+        );
+
+        let mut wrong_version = snapshot.clone();
+        wrong_version[4] += 1;
+        assert_eq!(
+            crate::load_ring_params(&wrong_version),
+            Err(crate::Error::InvalidRingParams)
+        );
+
+        assert_eq!(
+            crate::load_ring_params(&snapshot[..10]),
+            Err(crate::Error::InvalidRingParams)
+        );
+        assert_eq!(crate::ffi::load_ring_params(&corrupted), vec![1]);
+    }
+
+    #[test]
+    fn should_reject_ring_params_snapshot_from_other_pcs_params() {
+        use ark_vrf::reexports::ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+        use sha2::{Digest, Sha256};
+        super::init_test_pcs_params();
+        let snapshot = crate::serialize_ring_params(RingSize::Tiny, None).unwrap();
+
+        // Same layout, but derived from another SRS.
+        let (header, payload) = snapshot.split_at(9 + 32);
+        let mut reader = payload;
+        let mut ring_params =
+            crate::bandersnatch::RingProofParams::deserialize_uncompressed_unchecked(&mut reader)
+                .unwrap();
+        ring_params.pcs.powers_in_g1.swap(1, 2);
+        let mut payload = Vec::new();
+        ring_params.serialize_uncompressed(&mut payload).unwrap();
+        payload.extend_from_slice(reader);
+        let other = [&header[..9], &Sha256::digest(&payload)[..], &payload].concat();
+
+        assert!(crate::snapshot::parse_ring_params(&other).is_ok());
+        assert_eq!(
+            crate::load_ring_params(&other),
+            Err(crate::Error::InvalidRingParams)
+        );
+    }
+
+    #[test]
+    fn should_batch_verify_tickets_with_aux_data_layout() {
+        super::init_test_pcs_params();
+        let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
+        let prover_index = 2;
+        let vrf_input = [0xAB; 36];
+
+        let signature = generate_ring_vrf_impl(
+            &public_keys,
+            prover_index,
+            &seeds[prover_index],
+            &vrf_input,
+            &[],
+        )
+        .unwrap();
+        let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        let ticket_with_aux = |aux_data: &[u8]| {
+            let mut ticket = signature.clone();
+            ticket.extend_from_slice(&vrf_input);
+            ticket.extend_from_slice(&(aux_data.len() as u32).to_le_bytes());
+            ticket.extend_from_slice(aux_data);
+            ticket
+        };
+
+        // The signature was made without aux data.
+        let tickets_data = [ticket_with_aux(&[]), ticket_with_aux(&[])].concat();
+        let verify_results = crate::batch_verify_tickets_with_aux_impl(
+            RingSize::Tiny,
+            &commitment_bytes,
+            &tickets_data,
+            vrf_input.len(),
+        )
+        .unwrap();
+        let expected = output_hash(&seeds[prover_index], &vrf_input);
+        assert_eq!(verify_results, vec![expected, expected]);
+
+        // Binding different aux data makes the batch fail.
+        let tickets_data = [ticket_with_aux(&[]), ticket_with_aux(b"context")].concat();
+        assert_eq!(
+            crate::batch_verify_tickets_with_aux_impl(
+                RingSize::Tiny,
+                &commitment_bytes,
+                &tickets_data,
+                vrf_input.len(),
+            ),
+            Err(crate::Error::VerificationFailure)
+        );
+        let ffi_result = crate::ffi::batch_verify_tickets_with_aux(
+            RingSize::Tiny.size() as u32,
+            &commitment_bytes,
+            &tickets_data,
+            vrf_input.len() as u32,
+        );
+        assert_eq!(ffi_result, [vec![1u8], vec![0u8; 64]].concat());
+
+        // Aux data length pointing past the end of the input.
+        let mut truncated = ticket_with_aux(b"context");
+        truncated.pop();
+        assert_eq!(
+            crate::batch_verify_tickets_with_aux_impl(
+                RingSize::Tiny,
+                &commitment_bytes,
+                &truncated,
+                vrf_input.len(),
+            ),
+            Err(crate::Error::InvalidSignature)
+        );
+        let ffi_result = crate::ffi::batch_verify_tickets_with_aux(
+            RingSize::Tiny.size() as u32,
+            &commitment_bytes,
+            &truncated,
+            vrf_input.len() as u32,
+        );
+        assert_eq!(ffi_result, vec![1u8]);
+    }
+
+    #[test]
+    fn should_generate_ring_vrf_with_aux_data() {
+        use ark_vrf::reexports::ark_serialize::CanonicalDeserialize;
+        super::init_test_pcs_params();
+        let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
+        let prover_index = 3;
+        let input_len = 36;
+        let aux_data = b"aux context";
+
+        let mut inputs_data = Vec::new();
+        for attempt in 0..2u32 {
+            inputs_data.extend_from_slice(&[0xEF; 32]);
+            inputs_data.extend_from_slice(&attempt.to_le_bytes());
+        }
+
+        let results = batch_generate_ring_vrf_impl(
+            &public_keys,
+            prover_index,
+            &seeds[prover_index],
+            &inputs_data,
+            input_len,
+            aux_data,
+        );
+        let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        let commitment =
+            crate::RingCommitment::deserialize_compressed(&commitment_bytes[..]).unwrap();
+
+        let mut tickets_data = Vec::new();
+        for (vrf_input, result) in inputs_data.chunks(input_len).zip(&results) {
+            let signature = result.as_ref().unwrap();
+            let expected = output_hash(&seeds[prover_index], vrf_input);
+
+            let output = crate::Verifier::ring_vrf_verify(
+                RingSize::Tiny,
+                commitment.clone(),
+                vrf_input,
+                aux_data,
+                signature,
+            );
+            assert_eq!(output, Ok(expected));
+            let output = crate::Verifier::ring_vrf_verify(
+                RingSize::Tiny,
+                commitment.clone(),
+                vrf_input,
+                &[],
+                signature,
+            );
+            assert_eq!(output, Err(crate::Error::VerificationFailure));
+
+            tickets_data.extend_from_slice(signature);
+            tickets_data.extend_from_slice(vrf_input);
+            tickets_data.extend_from_slice(&(aux_data.len() as u32).to_le_bytes());
+            tickets_data.extend_from_slice(aux_data);
+        }
+
+        let verify_results = crate::batch_verify_tickets_with_aux_impl(
+            RingSize::Tiny,
+            &commitment_bytes,
+            &tickets_data,
+            input_len,
+        );
+        assert!(verify_results.is_ok());
+
+        let single = generate_ring_vrf_impl(
+            &public_keys,
+            prover_index,
+            &seeds[prover_index],
+            &inputs_data[..input_len],
+            aux_data,
+        )
+        .unwrap();
+        let output = crate::Verifier::ring_vrf_verify(
+            RingSize::Tiny,
+            commitment,
+            &inputs_data[..input_len],
+            aux_data,
+            &single,
+        );
+        assert!(output.is_ok());
+    }
+
+    #[test]
+    fn should_reject_unsupported_ring_sizes() {
+        super::init_test_pcs_params();
+        assert_eq!(RingSize::from_key_count(6), Ok(RingSize::Tiny));
+        assert_eq!(RingSize::from_key_count(1023), Ok(RingSize::Full));
+        assert_eq!(
+            RingSize::from_key_count(5),
+            Err(crate::Error::UnsupportedRingSize)
+        );
+
+        let (seeds, public_keys) = make_ring(RingSize::Tiny.size() - 1);
+        let ring_keys: Vec<u8> = seeds
+            .iter()
+            .flat_map(|s| derive_public_key_from_seed(s).unwrap())
+            .collect();
+        assert_eq!(crate::ffi::ring_commitment(&ring_keys), vec![1]);
+        assert_eq!(crate::ffi::serialize_ring_params(5, &[]), vec![1]);
+        assert_eq!(
+            batch_generate_ring_vrf_impl(&public_keys, 0, &seeds[0], &[0x5A; 32], 32, &[]),
+            vec![Err(crate::Error::UnsupportedRingSize)]
+        );
+    }
+
+    #[test]
+    fn should_generate_and_verify_on_full_ring() {
+        use ark_vrf::reexports::ark_serialize::CanonicalDeserialize;
+        super::init_test_pcs_params();
+        let (seeds, public_keys) = make_ring(RingSize::Full.size());
+        let prover_index = RingSize::Full.size() - 1;
+        let input_len = 36;
+
+        let mut inputs_data = Vec::new();
+        for attempt in 0..2u32 {
+            inputs_data.extend_from_slice(&[0x5A; 32]);
+            inputs_data.extend_from_slice(&attempt.to_le_bytes());
+        }
+
+        let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Full).unwrap();
+        let encoded_keys: Vec<u8> = seeds
+            .iter()
+            .flat_map(|seed| derive_public_key_from_seed(seed).unwrap())
+            .collect();
+        assert_eq!(
+            crate::ffi::ring_commitment(&encoded_keys),
+            [vec![0u8], commitment_bytes.clone()].concat()
+        );
+
+        let results = batch_generate_ring_vrf_impl(
+            &public_keys,
+            prover_index,
+            &seeds[prover_index],
+            &inputs_data,
+            input_len,
+            &[],
+        );
+
+        let mut tickets_data = Vec::new();
+        let mut expected = Vec::new();
+        for (vrf_input, result) in inputs_data.chunks(input_len).zip(&results) {
+            tickets_data.extend_from_slice(result.as_ref().unwrap());
+            tickets_data.extend_from_slice(vrf_input);
+            expected.push(output_hash(&seeds[prover_index], vrf_input));
+        }
+
+        let verify_results = crate::batch_verify_tickets_impl(
+            RingSize::Full,
+            &commitment_bytes,
+            &tickets_data,
+            input_len,
+        );
+        assert_eq!(verify_results, Ok(expected.clone()));
+
+        // The full ring commitment doesn't verify tiny ring proofs and vice versa.
+        let commitment =
+            crate::RingCommitment::deserialize_compressed(&commitment_bytes[..]).unwrap();
+        let signature = results[0].as_ref().unwrap();
+        let output = crate::Verifier::ring_vrf_verify(
+            RingSize::Full,
+            commitment.clone(),
+            &inputs_data[..input_len],
+            &[],
+            signature,
+        );
+        assert_eq!(output, Ok(expected[0]));
+        let output = crate::Verifier::ring_vrf_verify(
+            RingSize::Tiny,
+            commitment,
+            &inputs_data[..input_len],
+            &[],
+            signature,
+        );
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn should_generate_and_verify_pedersen_vrf() {
+        let seed = [7u8; 32];
+        let public_key = derive_public_key_from_seed(&seed).unwrap();
+        let input = b"pedersen input";
+        let aux_data = b"claim";
+
+        let (signature, blinding) = crate::generate_pedersen_vrf(&seed, input, aux_data).unwrap();
+        assert_eq!(signature.len(), crate::PEDERSEN_SIGNATURE_SIZE);
+
+        let (hash, key_commitment) =
+            crate::Verifier::pedersen_vrf_verify(input, aux_data, &signature).unwrap();
+        assert_eq!(hash, output_hash(&seed, input));
+        assert_eq!(
+            crate::pedersen_key_commitment(&public_key, &blinding),
+            Ok(key_commitment)
+        );
+        assert_ne!(key_commitment[..], public_key[..]);
+
+        let other_public_key = derive_public_key_from_seed(&[8u8; 32]).unwrap();
+        assert_ne!(
+            crate::pedersen_key_commitment(&other_public_key, &blinding),
+            Ok(key_commitment)
+        );
+
+        assert_eq!(
+            crate::Verifier::pedersen_vrf_verify(input, b"other", &signature),
+            Err(crate::Error::VerificationFailure)
+        );
+        assert_eq!(
+            crate::Verifier::pedersen_vrf_verify(input, aux_data, &signature[..100]),
+            Err(crate::Error::InvalidSignature)
+        );
+
+        let generated = crate::ffi::generate_pedersen_vrf(&seed, input, aux_data);
+        assert_eq!(generated[0], 0);
+        assert_eq!(
+            generated.len(),
+            1 + crate::PEDERSEN_SIGNATURE_SIZE + crate::PEDERSEN_BLINDING_SIZE
+        );
+        let verified = crate::ffi::verify_pedersen_vrf(input, b"other", &signature);
+        assert_eq!(verified, [vec![1u8], vec![0u8; 64]].concat());
+    }
+
+    #[test]
+    fn should_sign_with_imported_secret_keys() {
+        super::init_test_pcs_params();
+        let seed = [3u8; 32];
+        let input = b"key handle input";
+        let public_key = derive_public_key_from_seed(&seed).unwrap();
+
+        let seed_key = crate::import_secret_seed(&seed);
+        assert_eq!(crate::secret_key_public(seed_key), Ok(public_key.clone()));
+
+        // The exported scalar imports to the same key.
+        let scalar = crate::export_secret_scalar(seed_key).unwrap();
+        let scalar_key = crate::import_secret_scalar(&scalar[..]).unwrap();
+        assert_ne!(seed_key, scalar_key);
+        assert_eq!(crate::secret_key_public(scalar_key), Ok(public_key));
+
+        // IETF signatures are deterministic, so they match the seed-based ones.
+        let seal = generate_ietf_seal(&seed, input, b"aux").unwrap();
+        assert_eq!(
+            crate::generate_ietf_seal_with_key(scalar_key, input, b"aux"),
+            Ok(seal)
+        );
+        assert_eq!(
+            crate::compute_vrf_output_hash_with_key(scalar_key, input, VRF_OUTPUT_HASH_SIZE),
+            compute_vrf_output_hash(&seed, input, VRF_OUTPUT_HASH_SIZE)
+        );
+
+        let (_seeds, mut public_keys) = make_ring(RingSize::Tiny.size());
+        public_keys[1] = deserialize_public_key(&derive_public_key_from_seed(&seed).unwrap());
+        let signature =
+            crate::generate_ring_vrf_with_key_impl(&public_keys, 1, seed_key, input, &[]).unwrap();
+        let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        let verified = crate::batch_verify_tickets_impl(
+            RingSize::Tiny,
+            &commitment_bytes,
+            &[&signature[..], input].concat(),
+            input.len(),
+        );
+        assert_eq!(verified, Ok(vec![output_hash(&seed, input)]));
+
+        assert!(crate::release_secret_key(seed_key));
+        assert!(!crate::release_secret_key(seed_key));
+        assert_eq!(
+            crate::generate_ietf_seal_with_key(seed_key, input, &[]),
+            Err(crate::Error::UnknownKeyHandle)
+        );
+        assert_eq!(
+            crate::batch_generate_ring_vrf_with_key_impl(&public_keys, 1, seed_key, input, 8, &[]),
+            vec![Err(crate::Error::UnknownKeyHandle); 2]
+        );
+        assert_eq!(crate::ffi::export_secret_scalar(seed_key), vec![1]);
+
+        let encoded = crate::ffi::import_secret_scalar(&scalar[..]);
+        assert_eq!(encoded.len(), 5);
+        let handle = u32::from_le_bytes(encoded[1..].try_into().unwrap());
+        assert_eq!(
+            crate::ffi::export_secret_scalar(handle),
+            [&[0u8][..], &scalar[..]].concat()
+        );
+        assert_eq!(crate::ffi::release_secret_key(handle), vec![0]);
+        assert!(crate::release_secret_key(scalar_key));
+    }
+
+    #[test]
+    fn should_reject_invalid_secret_scalars() {
+        assert_eq!(
+            crate::import_secret_scalar(&[0u8; 32]),
+            Err(crate::Error::InvalidSecretKey)
+        );
+        assert_eq!(
+            crate::import_secret_scalar(&[0xFFu8; 32]),
+            Err(crate::Error::InvalidSecretKey)
+        );
+        assert_eq!(
+            crate::import_secret_scalar(&[1u8; 31]),
+            Err(crate::Error::InvalidSecretKey)
+        );
+        assert_eq!(crate::ffi::import_secret_scalar(&[0u8; 32]), vec![1]);
+    }
+
+    #[test]
+    fn should_move_keys_through_keystore() {
+        let params = crate::KdfParams {
+            log_n: 4,
+            ..Default::default()
+        };
+        let seeds = [[7u8; 32], [8u8; 32]];
+        let handles: Vec<_> = seeds.iter().map(|s| crate::import_secret_seed(s)).collect();
+        let file = crate::export_keystore(&handles, b"passphrase", params).unwrap();
+
+        let imported = crate::import_keystore(&file, b"passphrase").unwrap();
+        assert_eq!(imported.len(), 2);
+        for ((seed, handle), imported) in seeds.iter().zip(&handles).zip(&imported) {
+            assert_ne!(handle, imported);
+            assert_eq!(
+                crate::secret_key_public(*imported).unwrap(),
+                derive_public_key_from_seed(seed).unwrap()
+            );
+            assert_eq!(
+                crate::export_secret_scalar(*imported),
+                crate::export_secret_scalar(*handle)
+            );
+        }
+
+        assert!(matches!(
+            crate::import_keystore(&file, b"wrong"),
+            Err(crate::Error::Keystore(_))
+        ));
+        assert_eq!(
+            crate::export_keystore(&[0], b"passphrase", params),
+            Err(crate::Error::UnknownKeyHandle)
+        );
+
+        // Only key handles and public keys cross the FFI.
+        let result = crate::ffi::import_keystore(&file, b"passphrase");
+        assert_eq!(result.len(), 1 + 2 * (4 + crate::PUBLIC_KEY_SIZE));
+        assert_eq!(result[0], 0);
+        assert_eq!(
+            &result[5..37],
+            &derive_public_key_from_seed(&seeds[0]).unwrap()[..]
+        );
+        assert_eq!(crate::ffi::import_keystore(&file, b"wrong"), vec![1]);
+        assert_eq!(
+            crate::ffi::export_keystore(&[1, 2, 3], b"passphrase"),
+            vec![1]
+        );
+
+        for handle in handles.iter().chain(&imported) {
+            assert!(crate::release_secret_key(*handle));
+        }
+    }
+
+    #[test]
+    fn should_not_leak_secrets_into_results_or_panics() {
+        use crate::ffi;
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+
+        let seed: Vec<u8> = (0..32u8).map(|i| i.wrapping_mul(37) ^ 0xA5).collect();
+        let key = crate::import_secret_seed(&seed);
+        let scalar = crate::export_secret_scalar(key).unwrap();
+        assert!(crate::release_secret_key(key));
+        let secrets = [seed.clone(), scalar.to_vec()];
+
+        let (seeds, _) = make_ring(RingSize::Tiny.size());
+        let prover_index = 2u32;
+        let mut ring_keys: Vec<u8> = seeds
+            .iter()
+            .flat_map(|s| derive_public_key_from_seed(s).unwrap())
+            .collect();
+        ring_keys[64..96].copy_from_slice(&derive_public_key_from_seed(&seed).unwrap());
+        let input = [0x42u8; 32];
+        let aux_data = b"aux";
+
+        let handle = ffi::import_secret_seed(&seed);
+        let key = u32::from_le_bytes(handle[1..5].try_into().unwrap());
+        type FfiCall<'a> = Box<dyn Fn() -> Vec<u8> + 'a>;
+        let calls: Vec<(&str, FfiCall)> = vec![
+            (
+                "derive_public_key",
+                Box::new(|| ffi::derive_public_key(&seed)),
+            ),
+            (
+                "import_secret_seed",
+                Box::new(|| ffi::import_secret_seed(&seed)),
+            ),
+            (
+                "import_secret_scalar",
+                Box::new(|| ffi::import_secret_scalar(&scalar[..])),
+            ),
+            (
+                "import_short_scalar",
+                Box::new(|| ffi::import_secret_scalar(&scalar[1..])),
+            ),
+            (
+                "generate_seal",
+                Box::new(|| ffi::generate_seal(&seed, &input, aux_data)),
+            ),
+            (
+                "generate_seal_with_key",
+                Box::new(|| ffi::generate_seal_with_key(key, &input, aux_data)),
+            ),
+            (
+                "generate_pedersen_vrf",
+                Box::new(|| ffi::generate_pedersen_vrf(&seed, &input, aux_data)),
+            ),
+            (
+                "vrf_output_hash",
+                Box::new(|| ffi::vrf_output_hash(&seed, &input)),
+            ),
+            (
+                "generate_ring_vrf",
+                Box::new(|| {
+                    ffi::generate_ring_vrf_with_aux(
+                        &ring_keys,
+                        prover_index,
+                        &seed,
+                        &input,
+                        aux_data,
+                    )
+                }),
+            ),
+            (
+                "generate_ring_vrf_bad_index",
+                Box::new(|| {
+                    ffi::generate_ring_vrf_with_aux(&ring_keys, 100, &seed, &input, aux_data)
+                }),
+            ),
+            (
+                "generate_ring_vrf_bad_ring",
+                Box::new(|| {
+                    ffi::generate_ring_vrf_with_aux(
+                        &ring_keys[1..],
+                        prover_index,
+                        &seed,
+                        &input,
+                        aux_data,
+                    )
+                }),
+            ),
+            (
+                "batch_generate_ring_vrf_no_input",
+                Box::new(|| {
+                    ffi::batch_generate_ring_vrf_with_aux(
+                        &ring_keys,
+                        prover_index,
+                        &seed,
+                        &input,
+                        0,
+                        aux_data,
+                    )
+                }),
+            ),
+            (
+                "batch_generate_ring_vrf_for_validators",
+                Box::new(|| {
+                    ffi::batch_generate_ring_vrf_for_validators_with_aux(
+                        &ring_keys,
+                        &prover_index.to_le_bytes(),
+                        &seed,
+                        32,
+                        &input,
+                        32,
+                        aux_data,
+                    )
+                }),
+            ),
+            (
+                "batch_generate_ring_vrf_for_validators_bad_seeds",
+                Box::new(|| {
+                    ffi::batch_generate_ring_vrf_for_validators_with_aux(
+                        &ring_keys,
+                        &prover_index.to_le_bytes(),
+                        &seed,
+                        31,
+                        &input,
+                        32,
+                        aux_data,
+                    )
+                }),
+            ),
+            (
+                "export_keystore",
+                Box::new(|| ffi::export_keystore(&key.to_le_bytes(), b"passphrase")),
+            ),
+        ];
+
+        let assert_no_secret_in_text = |name: &str, text: &str| {
+            for secret in &secrets {
+                assert!(
+                    !text.contains(&hex::encode(secret)) && !text.contains(&format!("{secret:?}")),
+                    "{name} formatted a secret"
+                );
+            }
+        };
+
+        let mut panicked = Vec::new();
+        for (name, call) in &calls {
+            match catch_unwind(AssertUnwindSafe(call)) {
+                Ok(result) => {
+                    for secret in &secrets {
+                        assert!(
+                            !result.windows(secret.len()).any(|w| w == &secret[..]),
+                            "{name} returned a secret"
+                        );
+                    }
+                }
+                Err(payload) => {
+                    let message = payload
+                        .downcast_ref::<&str>()
+                        .map(|m| m.to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    assert_no_secret_in_text(name, &message);
+                    panicked.push(*name);
+                }
+            }
+        }
+        assert!(crate::release_secret_key(key));
+        assert!(panicked.is_empty(), "calls panicked: {panicked:?}");
+
+        // Core results (and errors) are checked through their `Debug` output,
+        // which is what ends up in logs and `unwrap` panics.
+        let ring_public_keys: Vec<_> = ring_keys
+            .chunks(crate::PUBLIC_KEY_SIZE)
+            .map(deserialize_public_key)
+            .collect();
+        let results = [
+            (
+                "import_short_scalar",
+                format!("{:?}", crate::import_secret_scalar(&scalar[1..])),
+            ),
+            (
+                "export_released_scalar",
+                format!("{:?}", crate::export_secret_scalar(key)),
+            ),
+            (
+                "generate_ietf_seal",
+                format!("{:?}", generate_ietf_seal(&seed, &input, aux_data)),
+            ),
+            (
+                "compute_vrf_output_hash_bad_len",
+                format!("{:?}", compute_vrf_output_hash(&seed, &input, 0)),
+            ),
+            (
+                "generate_ring_vrf_bad_index",
+                format!(
+                    "{:?}",
+                    generate_ring_vrf_impl(&ring_public_keys, 100, &seed, &input, aux_data)
+                ),
+            ),
+            (
+                "locate_prover_key_index",
+                format!(
+                    "{:?}",
+                    crate::locate_prover_key_index(&ring_public_keys, &seed)
+                ),
+            ),
+        ];
+        for (name, text) in &results {
+            assert_no_secret_in_text(name, text);
+        }
+    }
+
+    #[test]
+    fn should_score_then_prove_below_threshold() {
+        use ark_vrf::reexports::ark_serialize::CanonicalDeserialize;
+        super::init_test_pcs_params();
+        let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
+        let prover_index = 4;
+        let seed = &seeds[prover_index];
+        let input_len = 36;
+
+        let mut inputs_data = Vec::new();
+        for attempt in 0..4u32 {
+            inputs_data.extend_from_slice(&[0x5A; 32]);
+            inputs_data.extend_from_slice(&attempt.to_le_bytes());
+        }
+
+        let hashes: Vec<_> = crate::compute_vrf_output_hashes(seed, &inputs_data, input_len)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        for (input, hash) in inputs_data.chunks(input_len).zip(&hashes) {
+            assert_eq!(output_hash(seed, input), *hash);
+        }
+        assert_eq!(
+            crate::compute_vrf_output_hashes(seed, &inputs_data[1..], input_len)[3],
+            Err(crate::Error::InvalidPointData)
+        );
+
+        // Keep the two lowest attempts.
+        let mut sorted = hashes.clone();
+        sorted.sort();
+        let threshold = sorted[2];
+
+        let results = crate::batch_generate_ring_vrf_below_threshold_impl(
+            &public_keys,
+            prover_index,
+            seed,
+            &inputs_data,
+            input_len,
+            &threshold,
+            &[],
+        );
+        let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        let commitment =
+            crate::RingCommitment::deserialize_compressed(&commitment_bytes[..]).unwrap();
+        for ((input, hash), result) in inputs_data.chunks(input_len).zip(&hashes).zip(&results) {
+            if *hash < threshold {
+                let verified = crate::Verifier::ring_vrf_verify(
+                    RingSize::Tiny,
+                    commitment.clone(),
+                    input,
+                    &[],
+                    result.as_ref().unwrap(),
+                );
+                assert_eq!(verified.unwrap(), *hash);
+            } else {
+                assert_eq!(*result, Err(crate::Error::OutputAboveThreshold));
+            }
+        }
+
+        // Nothing to prove: every input is skipped, unless the prover index is bad.
+        let results = crate::batch_generate_ring_vrf_below_threshold_impl(
+            &public_keys,
+            prover_index,
+            seed,
+            &inputs_data,
+            input_len,
+            &[0u8; 32],
+            &[],
+        );
+        assert_eq!(results, vec![Err(crate::Error::OutputAboveThreshold); 4]);
+        let results = crate::batch_generate_ring_vrf_below_threshold_impl(
+            &public_keys,
+            public_keys.len(),
+            seed,
+            &inputs_data,
+            input_len,
+            &[0u8; 32],
+            &[],
+        );
+        assert_eq!(results, vec![Err(crate::Error::InvalidSignature); 4]);
+
+        let ring_keys: Vec<u8> = seeds
+            .iter()
+            .flat_map(|s| derive_public_key_from_seed(s).unwrap())
+            .collect();
+        let encoded = crate::ffi::batch_generate_ring_vrf_below_threshold(
+            &ring_keys,
+            prover_index as u32,
+            seed,
+            &inputs_data,
+            input_len as u32,
+            &[0u8; 32],
+            &[],
+        );
+        assert_eq!(encoded.len(), 4 * (1 + RING_SIGNATURE_SIZE));
+        assert!(encoded.chunks(1 + RING_SIGNATURE_SIZE).all(|r| r[0] == 2));
+        assert_eq!(
+            crate::ffi::batch_generate_ring_vrf_below_threshold(
+                &ring_keys,
+                prover_index as u32,
+                seed,
+                &inputs_data,
+                input_len as u32,
+                &[0u8; 31],
+                &[],
+            ),
+            vec![1]
+        );
+
+        let encoded = crate::ffi::vrf_output_hashes(seed, &inputs_data, input_len as u32);
+        assert_eq!(encoded.len(), 4 * 33);
+        assert_eq!(encoded[0], 0);
+        assert_eq!(encoded[1..33], hashes[0]);
+    }
+
+    #[test]
+    fn should_generate_header_seals_verified_by_verify_header_seals() {
+        let seed = [9u8; 32];
+        let signer_key = derive_public_key_from_seed(&seed).unwrap();
+        let seal_payload = b"jam_fallback_seal entropy";
+        let unsealed_header = b"unsealed header bytes";
+        let entropy_prefix = b"jam_entropy";
+
+        let (seal, entropy) =
+            crate::generate_header_seals(&seed, seal_payload, unsealed_header, entropy_prefix)
+                .unwrap();
+        assert_eq!(
+            seal,
+            generate_ietf_seal(&seed, seal_payload, unsealed_header).unwrap()
+        );
+
+        let (seal_hash, entropy_hash) = verify_header_seals_impl(
+            &signer_key,
+            &seal,
+            seal_payload,
+            unsealed_header,
+            &entropy,
+            entropy_prefix,
+        )
+        .unwrap();
+        let entropy_payload = [&entropy_prefix[..], &seal_hash[..]].concat();
+        assert_eq!(
+            entropy,
+            generate_ietf_seal(&seed, &entropy_payload, &[]).unwrap()
+        );
+        assert_eq!(entropy_hash, output_hash(&seed, &entropy_payload));
+
+        let key = crate::import_secret_seed(&seed);
+        let encoded = crate::ffi::generate_header_seals_with_key(
+            key,
+            seal_payload,
+            unsealed_header,
+            entropy_prefix,
+        );
+        assert_eq!(encoded, [&[0u8][..], &seal, &entropy].concat());
+        assert!(crate::release_secret_key(key));
+        assert_eq!(
+            crate::ffi::generate_header_seals_with_key(
+                key,
+                seal_payload,
+                unsealed_header,
+                entropy_prefix
+            ),
+            [vec![1u8], vec![0u8; 2 * crate::IETF_SIGNATURE_SIZE]].concat()
+        );
+    }
+
+    #[test]
+    fn should_batch_verify_header_seals() {
+        let entropy_prefix = b"jam_entropy";
+        let mut headers_data = Vec::new();
+        let mut expected = Vec::new();
+        for i in 0..4u8 {
+            let seed = [i; 32];
+            let signer_key = derive_public_key_from_seed(&seed).unwrap();
+            let seal_payload = [b"jam_fallback_seal".as_slice(), &[i; 32]].concat();
+            let unsealed_header = vec![i; 10 + i as usize];
+            let (seal, mut entropy) = crate::generate_header_seals(
+                &seed,
+                &seal_payload,
+                &unsealed_header,
+                entropy_prefix,
+            )
+            .unwrap();
+            if i == 2 {
+                entropy[40] ^= 1;
+            }
+            expected.push(verify_header_seals_impl(
+                &signer_key,
+                &seal,
+                &seal_payload,
+                &unsealed_header,
+                &entropy,
+                entropy_prefix,
+            ));
+
+            headers_data.extend_from_slice(&signer_key);
+            headers_data.extend_from_slice(&seal);
+            headers_data.extend_from_slice(&entropy);
+            for field in [&seal_payload[..], &unsealed_header, entropy_prefix] {
+                headers_data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+                headers_data.extend_from_slice(field);
+            }
+        }
+        assert!(expected[2].is_err());
+        assert_eq!(expected.iter().filter(|r| r.is_ok()).count(), 3);
+
+        let encoded = crate::ffi::batch_verify_header_seals(&headers_data);
+        assert_eq!(encoded.len(), 4 * 65);
+        for (result, expected) in encoded.chunks(65).zip(&expected) {
+            match expected {
+                Ok((seal, entropy)) => {
+                    assert_eq!(result[0], 0);
+                    assert_eq!(result[1..33], seal[..]);
+                    assert_eq!(result[33..], entropy[..]);
+                }
+                Err(_) => assert_eq!(result, [[1u8].as_slice(), &[0u8; 64]].concat()),
+            }
+        }
+
+        assert_eq!(
+            crate::ffi::batch_verify_header_seals(&headers_data[..headers_data.len() - 1]),
+            vec![1]
+        );
+        assert!(crate::ffi::batch_verify_header_seals(&[]).is_empty());
+    }
+
+    #[test]
+    fn should_batch_ietf_vrf_verify() {
+        let mut signatures_data = Vec::new();
+        let mut expected = Vec::new();
+        for i in 0..5u8 {
+            let seed = [i; 32];
+            let signer_key = derive_public_key_from_seed(&seed).unwrap();
+            let input = vec![i; 1 + i as usize];
+            let aux_data = vec![i + 100; i as usize];
+            let mut seal = generate_ietf_seal(&seed, &input, &aux_data).unwrap();
+            match i {
+                1 => seal[70] ^= 1,
+                3 => seal[0] = 0xff,
+                _ => {}
+            }
+            expected.push(verify_seal_impl(
+                &signer_key,
+                &seal,
+                &input,
+                &aux_data,
+                VRF_OUTPUT_HASH_SIZE,
+            ));
+
+            signatures_data.extend_from_slice(&signer_key);
+            signatures_data.extend_from_slice(&seal);
+            for field in [&input, &aux_data] {
+                signatures_data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+                signatures_data.extend_from_slice(field);
+            }
+        }
+        assert!(expected[1].is_err() && expected[3].is_err());
+        assert_eq!(expected.iter().filter(|r| r.is_ok()).count(), 3);
+
+        let encoded = crate::ffi::batch_ietf_vrf_verify(&signatures_data);
+        assert_eq!(encoded.len(), 5 * 33);
+        for (result, expected) in encoded.chunks(33).zip(&expected) {
+            match expected {
+                Ok(hash) => {
+                    assert_eq!(result[0], 0);
+                    assert_eq!(result[1..], hash[..]);
+                }
+                Err(_) => assert_eq!(result, [[1u8].as_slice(), &[0u8; 32]].concat()),
+            }
+        }
+
+        assert_eq!(
+            crate::ffi::batch_ietf_vrf_verify(&signatures_data[..signatures_data.len() - 1]),
+            vec![1]
+        );
+        assert!(crate::ffi::batch_ietf_vrf_verify(&[]).is_empty());
+    }
+
+    #[test]
+    fn should_get_vrf_output_from_ietf_and_ring_signatures() {
+        super::init_test_pcs_params();
+        let (seeds, public_keys) = make_ring(RingSize::Tiny.size());
+        let vrf_input = b"ticket attempt";
+        let seal = generate_ietf_seal(&seeds[3], vrf_input, b"aux").unwrap();
+        let ring_signature =
+            generate_ring_vrf_impl(&public_keys, 3, &seeds[3], vrf_input, &[]).unwrap();
+
+        let expected = output_hash(&seeds[3], vrf_input);
+        for signature in [&seal[..], &ring_signature] {
+            assert_eq!(
+                crate::vrf_output_from_signature(signature, VRF_OUTPUT_HASH_SIZE).unwrap(),
+                expected
+            );
+            let full_hash = crate::vrf_output_from_signature(signature, 64).unwrap();
+            assert_eq!(full_hash[..32], expected);
+
+            assert_eq!(
+                crate::ffi::vrf_output_from_signature(signature),
+                [[0u8].as_slice(), &expected].concat()
+            );
+            assert_eq!(
+                crate::ffi::vrf_output_full_hash_from_signature(signature),
+                [[0u8].as_slice(), &full_hash].concat()
+            );
+        }
+
+        // Not verified: a tampered proof still yields the output.
+        let mut tampered = seal.clone();
+        tampered[80] ^= 1;
+        assert!(
+            verify_seal_impl(
+                &derive_public_key_from_seed(&seeds[3]).unwrap(),
+                &tampered,
+                vrf_input,
+                b"aux",
+                VRF_OUTPUT_HASH_SIZE,
+            )
+            .is_err()
+        );
+        assert_eq!(
+            crate::vrf_output_from_signature(&tampered, VRF_OUTPUT_HASH_SIZE).unwrap(),
+            expected
+        );
+
+        assert!(crate::vrf_output_from_signature(&seal[..95], VRF_OUTPUT_HASH_SIZE).is_err());
+        let mut invalid_output = seal.clone();
+        invalid_output[..32].fill(0xff);
+        assert!(crate::vrf_output_from_signature(&invalid_output, VRF_OUTPUT_HASH_SIZE).is_err());
+        assert_eq!(crate::ffi::vrf_output_from_signature(&[]), vec![1]);
+    }
+
+    #[test]
+    fn should_return_vrf_output_hash_of_requested_length() {
+        let seed = [7u8; 32];
+        let input = b"beacon round";
+        let aux_data = b"aux";
+        let public_key = derive_public_key_from_seed(&seed).unwrap();
+        let seal = generate_ietf_seal(&seed, input, aux_data).unwrap();
+
+        let full_hash = compute_vrf_output_hash(&seed, input, 64).unwrap();
+        assert_eq!(full_hash.len(), 64);
+        assert_eq!(full_hash[..32], output_hash(&seed, input));
+        for output_len in [1, 16, 32, 48, 64] {
+            let expected = &full_hash[..output_len];
+            assert_eq!(
+                compute_vrf_output_hash(&seed, input, output_len).unwrap(),
+                expected
+            );
+            assert_eq!(
+                verify_seal_impl(&public_key, &seal, input, aux_data, output_len).unwrap(),
+                expected
+            );
+            assert_eq!(
+                crate::vrf_output_from_signature(&seal, output_len).unwrap(),
+                expected
+            );
+        }
+
+        for output_len in [0, 65] {
+            assert_eq!(
+                compute_vrf_output_hash(&seed, input, output_len),
+                Err(crate::Error::InvalidOutputLength)
+            );
+            assert_eq!(
+                verify_seal_impl(&public_key, &seal, input, aux_data, output_len),
+                Err(crate::Error::InvalidOutputLength)
+            );
+        }
+
+        // The FFI keeps returning 32-byte hashes.
+        assert_eq!(
+            crate::ffi::vrf_output_hash(&seed, input),
+            [[0u8].as_slice(), &full_hash[..32]].concat()
+        );
+        assert_eq!(
+            crate::ffi::verify_seal(&public_key, &seal, input, aux_data),
+            [[0u8].as_slice(), &full_hash[..32]].concat()
+        );
+    }
+
+    #[test]
+    fn should_locate_prover_key_index_from_secret() {
+        super::init_test_pcs_params();
+        let (seeds, mut public_keys) = make_ring(RingSize::Tiny.size());
+        assert_eq!(
+            crate::locate_prover_key_index(&public_keys, &seeds[5]),
+            Ok(5)
+        );
+
+        let vrf_input = b"located attempt";
+        let signature =
+            crate::generate_ring_vrf_for_secret_impl(&public_keys, &seeds[5], vrf_input, &[])
+                .unwrap();
+        use ark_vrf::reexports::ark_serialize::CanonicalDeserialize;
+        let commitment_bytes = compute_ring_commitment(&public_keys, RingSize::Tiny).unwrap();
+        let commitment =
+            crate::RingCommitment::deserialize_compressed(&commitment_bytes[..]).unwrap();
+        assert_eq!(
+            crate::Verifier::ring_vrf_verify(
+                RingSize::Tiny,
+                commitment,
+                vrf_input,
+                &[],
+                &signature
+            ),
+            Ok(output_hash(&seeds[5], vrf_input))
+        );
+
+        let ring_keys: Vec<u8> = seeds
+            .iter()
+            .flat_map(|seed| derive_public_key_from_seed(seed).unwrap())
+            .collect();
+        assert_eq!(
+            crate::ffi::locate_prover_key_index(&ring_keys, &seeds[5]),
+            vec![0, 5, 0, 0, 0]
+        );
+        assert_eq!(
+            crate::ffi::locate_prover_key_index(&ring_keys, &[0xaa; 32]),
+            vec![1]
+        );
+        assert_eq!(
+            crate::generate_ring_vrf_for_secret_impl(&public_keys, &[0xaa; 32], vrf_input, &[]),
+            Err(crate::Error::ProverKeyNotInRing)
+        );
+
+        public_keys[1] = public_keys[5];
+        assert_eq!(
+            crate::locate_prover_key_index(&public_keys, &seeds[5]),
+            Err(crate::Error::DuplicateProverKey)
+        );
+        assert_eq!(
+            crate::generate_ring_vrf_for_secret_impl(&public_keys, &seeds[5], vrf_input, &[]),
+            Err(crate::Error::DuplicateProverKey)
+        );
+    }
+
+    #[test]
+    fn should_update_ring_commitment_like_full_recompute() {
+        super::init_test_pcs_params();
+        for ring_size in [RingSize::Tiny, RingSize::Full] {
+            let (_, previous_keys) = make_ring(ring_size.size());
+            let previous_commitment = compute_ring_commitment(&previous_keys, ring_size).unwrap();
+
+            let (_, new_keys) = make_ring(ring_size.size() + 3);
+            let last = ring_size.size() - 1;
+            let replacements = [
+                (0, new_keys[ring_size.size()]),
+                (last, new_keys[ring_size.size() + 1]),
+                (0, new_keys[ring_size.size() + 2]),
+                (2, deserialize_public_key(&[0u8; 32])),
+            ];
+            let mut keys = previous_keys.clone();
+            for (index, key) in replacements {
+                keys[index] = key;
+            }
+
+            let updated = crate::update_ring_commitment(
+                &previous_keys,
+                &previous_commitment,
+                &replacements,
+                ring_size,
+            )
+            .unwrap();
+            assert_eq!(updated, compute_ring_commitment(&keys, ring_size).unwrap());
+            assert_eq!(
+                crate::update_ring_commitment(&previous_keys, &previous_commitment, &[], ring_size),
+                Ok(previous_commitment.clone())
+            );
+            assert_eq!(
+                crate::update_ring_commitment(
+                    &previous_keys,
+                    &previous_commitment,
+                    &[(ring_size.size(), new_keys[0])],
+                    ring_size
+                ),
+                Err(crate::Error::InvalidKeyIndex)
+            );
+        }
+
+        let (_, previous_keys) = make_ring(RingSize::Tiny.size());
+        let previous_commitment = compute_ring_commitment(&previous_keys, RingSize::Tiny).unwrap();
+        let previous_keys_data: Vec<u8> = (0..RingSize::Tiny.size())
+            .flat_map(|i| derive_public_key_from_seed(&i.to_le_bytes()).unwrap())
+            .collect();
+        let new_key = derive_public_key_from_seed(b"next epoch").unwrap();
+        let mut keys = previous_keys.clone();
+        keys[4] = deserialize_public_key(&new_key);
+
+        let replacements = [&4u32.to_le_bytes()[..], &new_key].concat();
+        assert_eq!(
+            crate::ffi::update_ring_commitment(
+                &previous_keys_data,
+                &previous_commitment,
+                &replacements
+            ),
+            [
+                vec![0],
+                compute_ring_commitment(&keys, RingSize::Tiny).unwrap()
+            ]
+            .concat()
+        );
+        assert_eq!(
+            crate::ffi::update_ring_commitment(
+                &previous_keys_data,
+                &previous_commitment,
+                &replacements[1..]
+            ),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn should_replace_offenders_with_padding_in_ring_commitment() {
+        super::init_test_pcs_params();
+        let (_, keys) = make_ring(RingSize::Tiny.size());
+        let mut expected_keys = keys.clone();
+        expected_keys[1] = crate::padding_public_key();
+        expected_keys[4] = crate::padding_public_key();
+        let expected = compute_ring_commitment(&expected_keys, RingSize::Tiny).unwrap();
+        assert_ne!(
+            expected,
+            compute_ring_commitment(&keys, RingSize::Tiny).unwrap()
+        );
+        // Same as zeroing the offenders' keys, as callers used to do.
+        let zeroed_keys: Vec<_> = (0..keys.len())
+            .map(|i| match i {
+                1 | 4 => deserialize_public_key(&[0u8; 32]),
+                i => keys[i],
+            })
+            .collect();
+        assert_eq!(
+            compute_ring_commitment(&zeroed_keys, RingSize::Tiny).unwrap(),
+            expected
+        );
+
+        let by_index = crate::ring_commitment_with_offenders(
+            &keys,
+            crate::Offenders::Indices(&[4, 1]),
+            RingSize::Tiny,
+        );
+        assert_eq!(by_index, Ok(expected.clone()));
+
+        let ed25519_keys: Vec<[u8; 32]> = (0..keys.len()).map(|i| [i as u8 + 1; 32]).collect();
+        let by_ed25519_key = crate::ring_commitment_with_offenders(
+            &keys,
+            crate::Offenders::Ed25519Keys {
+                validator_keys: &ed25519_keys,
+                offenders: &[[2u8; 32], [5u8; 32], [0xee; 32]],
+            },
+            RingSize::Tiny,
+        );
+        assert_eq!(by_ed25519_key, Ok(expected.clone()));
+
+        assert_eq!(
+            crate::ring_commitment_with_offenders(
+                &keys,
+                crate::Offenders::Indices(&[keys.len()]),
+                RingSize::Tiny
+            ),
+            Err(crate::Error::InvalidKeyIndex)
+        );
+        assert_eq!(
+            crate::ring_commitment_with_offenders(
+                &keys,
+                crate::Offenders::Ed25519Keys {
+                    validator_keys: &ed25519_keys[1..],
+                    offenders: &[],
+                },
+                RingSize::Tiny
+            ),
+            Err(crate::Error::MismatchedValidatorKeys)
+        );
+
+        let keys_data: Vec<u8> = (0..keys.len())
+            .flat_map(|i| derive_public_key_from_seed(&i.to_le_bytes()).unwrap())
+            .collect();
+        let expected_ffi = [vec![0], expected].concat();
+        assert_eq!(
+            crate::ffi::ring_commitment_with_offenders(
+                &keys_data,
+                &[1u32.to_le_bytes(), 4u32.to_le_bytes()].concat()
+            ),
+            expected_ffi
+        );
+        assert_eq!(
+            crate::ffi::ring_commitment_with_ed25519_offenders(
+                &keys_data,
+                &ed25519_keys.concat(),
+                &[[5u8; 32], [2u8; 32]].concat()
+            ),
+            expected_ffi
+        );
+        assert_eq!(
+            crate::ffi::ring_commitment_with_offenders(&keys_data, &[1, 0, 0]),
+            vec![1]
+        );
+        assert_eq!(
+            crate::ffi::ring_commitment_with_ed25519_offenders(
+                &keys_data,
+                &ed25519_keys.concat(),
+                &[5u8; 31]
+            ),
+            vec![1]
+        );
+    }
 }
@@ -0,0 +1,271 @@
+//! Conformance tests against the ark-vrf test vectors for the
+//! `bandersnatch_sha-512_ell2` suite (the one used by JAM).
+//!
+//! The scheme vectors are copied verbatim from `ark-vrf` into `data/vectors`.
+//! Its ring vectors only cover the tiny ring.
+//!
+//! `data/regression/safrole_tickets.json` is a regression fixture, not a
+//! conformance vector: the Safrole ticket extrinsics for a tiny and a full ring
+//! were generated with this crate. They pin the ring commitment (`gamma_z`), the
+//! ticket ids and the verification results, but can't catch a divergence from
+//! other JAM implementations.
+
+#[cfg(test)]
+mod tests {
+    use ark_vrf::pedersen::{Prover as _, Verifier as _};
+    use ark_vrf::reexports::ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use serde_json::Value;
+
+    use crate::bandersnatch::{Input, Output, PedersenProof, Public, ScalarField, Secret};
+    use crate::{
+        Error, IetfVrfSignature, RING_SIGNATURE_SIZE, RingCommitment, RingSize, Verifier,
+        batch_verify_tickets_impl, batch_verify_tickets_with_aux_impl, compute_ring_commitment,
+        copy_vrf_output_hash, deserialize_public_key, pedersen_key_commitment, vrf_input_point,
+        vrf_output_from_signature,
+    };
+
+    fn load_vectors(json: &str) -> Vec<Value> {
+        serde_json::from_str(json).expect("valid test vectors")
+    }
+
+    fn field(vector: &Value, name: &str) -> Vec<u8> {
+        let value = vector[name].as_str().expect("field is a hex string");
+        hex::decode(value).expect("field is valid hex")
+    }
+
+    fn secret(vector: &Value) -> Secret {
+        let sk = ScalarField::deserialize_compressed(&field(vector, "sk")[..]).unwrap();
+        Secret::from_scalar(sk)
+    }
+
+    fn serialize(value: impl CanonicalSerialize) -> Vec<u8> {
+        let mut buf = Vec::new();
+        value.serialize_compressed(&mut buf).unwrap();
+        buf
+    }
+
+    /// Check the fields shared by all schemes: keys, VRF input and output.
+    ///
+    /// Returns the VRF input and output.
+    fn check_base(vector: &Value) -> (Input, Output) {
+        let secret = secret(vector);
+        assert_eq!(serialize(secret.public()), field(vector, "pk"));
+        assert!(
+            field(vector, "salt").is_empty(),
+            "salted inputs are not supported"
+        );
+
+        let input = vrf_input_point(&field(vector, "alpha")).unwrap();
+        assert_eq!(serialize(input), field(vector, "h"));
+
+        let output = secret.output(input);
+        assert_eq!(serialize(output), field(vector, "gamma"));
+        assert_eq!(
+            copy_vrf_output_hash(output)[..],
+            field(vector, "beta")[..32]
+        );
+
+        (input, output)
+    }
+
+    fn pedersen_proof_bytes(vector: &Value) -> Vec<u8> {
+        ["proof_pk_com", "proof_r", "proof_ok", "proof_s", "proof_sb"]
+            .iter()
+            .flat_map(|name| field(vector, name))
+            .collect()
+    }
+
+    #[test]
+    fn should_match_ietf_vectors() {
+        let vectors = load_vectors(include_str!(
+            "../../data/vectors/bandersnatch_sha-512_ell2_ietf.json"
+        ));
+
+        for vector in &vectors {
+            let (input, output) = check_base(vector);
+            let ad = field(vector, "ad");
+
+            let proof = ark_vrf::ietf::Prover::prove(&secret(vector), input, output, &ad);
+            let signature = serialize(IetfVrfSignature { output, proof });
+            let expected = [
+                field(vector, "gamma"),
+                field(vector, "proof_c"),
+                field(vector, "proof_s"),
+            ]
+            .concat();
+            assert_eq!(signature, expected, "{}", vector["comment"]);
+
+            let public = deserialize_public_key(&field(vector, "pk"));
+            let verified =
+                Verifier::ietf_vrf_verify(&field(vector, "alpha"), &ad, &signature, public);
+            assert_eq!(verified.unwrap()[..], field(vector, "beta")[..32]);
+        }
+    }
+
+    #[test]
+    fn should_match_pedersen_vectors() {
+        let vectors = load_vectors(include_str!(
+            "../../data/vectors/bandersnatch_sha-512_ell2_pedersen.json"
+        ));
+
+        for vector in &vectors {
+            let (input, output) = check_base(vector);
+            let ad = field(vector, "ad");
+
+            let (proof, blinding) = secret(vector).prove(input, output, &ad);
+            assert_eq!(serialize(blinding), field(vector, "blinding"));
+            assert_eq!(
+                serialize(proof),
+                pedersen_proof_bytes(vector),
+                "{}",
+                vector["comment"]
+            );
+
+            let proof =
+                PedersenProof::deserialize_compressed(&pedersen_proof_bytes(vector)[..]).unwrap();
+            assert!(Public::verify(input, output, &ad, &proof).is_ok());
+
+            let signature = [field(vector, "gamma"), pedersen_proof_bytes(vector)].concat();
+            let (output_hash, key_commitment) =
+                Verifier::pedersen_vrf_verify(&field(vector, "alpha"), &ad, &signature).unwrap();
+            assert_eq!(output_hash[..], field(vector, "beta")[..32]);
+            assert_eq!(key_commitment[..], field(vector, "proof_pk_com")[..]);
+            assert_eq!(
+                pedersen_key_commitment(&field(vector, "pk"), &field(vector, "blinding")),
+                Ok(key_commitment)
+            );
+        }
+    }
+
+    #[test]
+    fn should_match_ring_vectors() {
+        crate::test::init_test_pcs_params();
+        let vectors = load_vectors(include_str!(
+            "../../data/vectors/bandersnatch_sha-512_ell2_ring.json"
+        ));
+
+        for vector in &vectors {
+            check_base(vector);
+            let ad = field(vector, "ad");
+            let alpha = field(vector, "alpha");
+            let beta = field(vector, "beta");
+
+            let ring_keys: Vec<_> = field(vector, "ring_pks")
+                .chunks(crate::PUBLIC_KEY_SIZE)
+                .map(deserialize_public_key)
+                .collect();
+            let commitment_bytes = compute_ring_commitment(&ring_keys, RingSize::Tiny).unwrap();
+            assert_eq!(
+                commitment_bytes,
+                field(vector, "ring_pks_com"),
+                "{}",
+                vector["comment"]
+            );
+
+            // The ring proof is randomized, so only the vector's proof
+            // verification is checked.
+            let signature = [
+                field(vector, "gamma"),
+                pedersen_proof_bytes(vector),
+                field(vector, "ring_proof"),
+            ]
+            .concat();
+            assert_eq!(signature.len(), RING_SIGNATURE_SIZE);
+
+            let commitment = RingCommitment::deserialize_compressed(&commitment_bytes[..]).unwrap();
+            let verified =
+                Verifier::ring_vrf_verify(RingSize::Tiny, commitment, &alpha, &ad, &signature);
+            assert_eq!(verified.unwrap()[..], beta[..32]);
+
+            let ticket = [
+                signature,
+                alpha.clone(),
+                (ad.len() as u32).to_le_bytes().to_vec(),
+                ad,
+            ]
+            .concat();
+            let verified = batch_verify_tickets_with_aux_impl(
+                RingSize::Tiny,
+                &commitment_bytes,
+                &ticket,
+                alpha.len(),
+            );
+            assert_eq!(verified.unwrap()[0][..], beta[..32]);
+        }
+    }
+
+    /// Safrole ticket VRF input: `"jam_ticket_seal" || eta2 || attempt`.
+    fn ticket_vrf_input(eta2: &[u8], attempt: u8) -> Vec<u8> {
+        [b"jam_ticket_seal".as_slice(), eta2, &[attempt]].concat()
+    }
+
+    #[test]
+    fn should_match_safrole_ticket_regression_fixture() {
+        crate::test::init_test_pcs_params();
+        let vectors = load_vectors(include_str!("../../data/regression/safrole_tickets.json"));
+
+        for vector in &vectors {
+            let comment = &vector["comment"];
+            let ring_size = match vector["ring_size"].as_u64() {
+                Some(6) => RingSize::Tiny,
+                Some(1023) => RingSize::Full,
+                size => panic!("unsupported ring size {size:?}"),
+            };
+            let eta2 = field(vector, "eta2");
+
+            let ring_keys: Vec<_> = field(vector, "ring_pks")
+                .chunks(crate::PUBLIC_KEY_SIZE)
+                .map(deserialize_public_key)
+                .collect();
+            let commitment_bytes = compute_ring_commitment(&ring_keys, ring_size).unwrap();
+            assert_eq!(commitment_bytes, field(vector, "ring_pks_com"), "{comment}");
+            let commitment = RingCommitment::deserialize_compressed(&commitment_bytes[..]).unwrap();
+
+            let mut valid_tickets = Vec::new();
+            let mut invalid_tickets = Vec::new();
+            let mut ids = Vec::new();
+            for ticket in vector["tickets"].as_array().expect("tickets array") {
+                let attempt = ticket["attempt"].as_u64().expect("attempt number") as u8;
+                let vrf_input = ticket_vrf_input(&eta2, attempt);
+                let signature = field(ticket, "signature");
+
+                let verified = Verifier::ring_vrf_verify(
+                    ring_size,
+                    commitment.clone(),
+                    &vrf_input,
+                    &[],
+                    &signature,
+                );
+                if ticket["valid"].as_bool().expect("validity flag") {
+                    let id = field(ticket, "id");
+                    assert_eq!(verified.unwrap()[..], id[..], "{comment}");
+                    assert_eq!(vrf_output_from_signature(&signature, 32), Ok(id.clone()));
+                    valid_tickets.extend([signature, vrf_input].concat());
+                    ids.push(id);
+                } else {
+                    assert_eq!(verified, Err(Error::VerificationFailure), "{comment}");
+                    invalid_tickets.extend([signature, vrf_input].concat());
+                }
+            }
+
+            let input_len = ticket_vrf_input(&eta2, 0).len();
+            let verified =
+                batch_verify_tickets_impl(ring_size, &commitment_bytes, &valid_tickets, input_len)
+                    .unwrap();
+            assert_eq!(verified.concat(), ids.concat(), "{comment}");
+
+            if !invalid_tickets.is_empty() {
+                assert_eq!(
+                    batch_verify_tickets_impl(
+                        ring_size,
+                        &commitment_bytes,
+                        &[valid_tickets, invalid_tickets].concat(),
+                        input_len,
+                    ),
+                    Err(Error::VerificationFailure),
+                    "{comment}"
+                );
+            }
+        }
+    }
+}
@@ -1,12 +1,66 @@
 use bandersnatch_core::ffi;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use zeroize::Zeroizing;
+
+#[napi]
+pub fn init_pcs_params(bytes: Buffer) -> Buffer {
+    ffi::init_pcs_params(bytes.as_ref()).into()
+}
+
+#[napi]
+pub fn serialize_ring_params(ring_size: u32, commitment: Buffer) -> Buffer {
+    ffi::serialize_ring_params(ring_size, commitment.as_ref()).into()
+}
+
+#[napi]
+pub fn load_ring_params(snapshot: Buffer) -> Buffer {
+    ffi::load_ring_params(snapshot.as_ref()).into()
+}
 
 #[napi]
 pub fn ring_commitment(keys: Buffer) -> Buffer {
     ffi::ring_commitment(keys.as_ref()).into()
 }
 
+#[napi]
+pub fn ring_commitment_with_offenders(keys: Buffer, offender_indices: Buffer) -> Buffer {
+    ffi::ring_commitment_with_offenders(keys.as_ref(), offender_indices.as_ref()).into()
+}
+
+#[napi]
+pub fn ring_commitment_with_ed25519_offenders(
+    keys: Buffer,
+    ed25519_keys: Buffer,
+    offenders: Buffer,
+) -> Buffer {
+    ffi::ring_commitment_with_ed25519_offenders(
+        keys.as_ref(),
+        ed25519_keys.as_ref(),
+        offenders.as_ref(),
+    )
+    .into()
+}
+
+#[napi]
+pub fn update_ring_commitment(
+    previous_keys: Buffer,
+    previous_commitment: Buffer,
+    replacements: Buffer,
+) -> Buffer {
+    ffi::update_ring_commitment(
+        previous_keys.as_ref(),
+        previous_commitment.as_ref(),
+        replacements.as_ref(),
+    )
+    .into()
+}
+
+#[napi]
+pub fn clear_ring_cache() {
+    ffi::clear_ring_cache()
+}
+
 #[napi]
 pub fn derive_public_key(seed: Buffer) -> Buffer {
     ffi::derive_public_key(seed.as_ref()).into()
@@ -32,6 +86,16 @@ pub fn verify_header_seals(
     .into()
 }
 
+#[napi]
+pub fn batch_verify_header_seals(headers_data: Buffer) -> Buffer {
+    ffi::batch_verify_header_seals(headers_data.as_ref()).into()
+}
+
+#[napi]
+pub fn batch_ietf_vrf_verify(signatures_data: Buffer) -> Buffer {
+    ffi::batch_ietf_vrf_verify(signatures_data.as_ref()).into()
+}
+
 #[napi]
 pub fn verify_seal(
     signer_key: Buffer,
@@ -48,11 +112,42 @@ pub fn verify_seal(
     .into()
 }
 
+#[napi]
+pub fn generate_header_seals(
+    secret_seed: Buffer,
+    seal_payload: Buffer,
+    unsealed_header: Buffer,
+    entropy_prefix: Buffer,
+) -> Buffer {
+    ffi::generate_header_seals(
+        secret_seed.as_ref(),
+        seal_payload.as_ref(),
+        unsealed_header.as_ref(),
+        entropy_prefix.as_ref(),
+    )
+    .into()
+}
+
 #[napi]
 pub fn generate_seal(secret_seed: Buffer, input: Buffer, aux_data: Buffer) -> Buffer {
     ffi::generate_seal(secret_seed.as_ref(), input.as_ref(), aux_data.as_ref()).into()
 }
 
+#[napi]
+pub fn generate_pedersen_vrf(secret_seed: Buffer, input: Buffer, aux_data: Buffer) -> Buffer {
+    ffi::generate_pedersen_vrf(secret_seed.as_ref(), input.as_ref(), aux_data.as_ref()).into()
+}
+
+#[napi]
+pub fn verify_pedersen_vrf(input: Buffer, aux_data: Buffer, signature: Buffer) -> Buffer {
+    ffi::verify_pedersen_vrf(input.as_ref(), aux_data.as_ref(), signature.as_ref()).into()
+}
+
+#[napi]
+pub fn pedersen_key_commitment(public_key: Buffer, blinding: Buffer) -> Buffer {
+    ffi::pedersen_key_commitment(public_key.as_ref(), blinding.as_ref()).into()
+}
+
 #[napi]
 pub fn vrf_output_hash(secret_seed: Buffer, input: Buffer) -> Buffer {
     ffi::vrf_output_hash(secret_seed.as_ref(), input.as_ref()).into()
@@ -74,6 +169,45 @@ pub fn generate_ring_vrf(
     .into()
 }
 
+#[napi]
+pub fn generate_ring_vrf_with_aux(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    secret_seed: Buffer,
+    vrf_input_data: Buffer,
+    aux_data: Buffer,
+) -> Buffer {
+    ffi::generate_ring_vrf_with_aux(
+        ring_keys.as_ref(),
+        prover_key_index,
+        secret_seed.as_ref(),
+        vrf_input_data.as_ref(),
+        aux_data.as_ref(),
+    )
+    .into()
+}
+
+#[napi]
+pub fn locate_prover_key_index(ring_keys: Buffer, secret_seed: Buffer) -> Buffer {
+    ffi::locate_prover_key_index(ring_keys.as_ref(), secret_seed.as_ref()).into()
+}
+
+#[napi]
+pub fn generate_ring_vrf_for_secret(
+    ring_keys: Buffer,
+    secret_seed: Buffer,
+    vrf_input_data: Buffer,
+    aux_data: Buffer,
+) -> Buffer {
+    ffi::generate_ring_vrf_for_secret(
+        ring_keys.as_ref(),
+        secret_seed.as_ref(),
+        vrf_input_data.as_ref(),
+        aux_data.as_ref(),
+    )
+    .into()
+}
+
 #[napi]
 pub fn batch_generate_ring_vrf(
     ring_keys: Buffer,
@@ -92,6 +226,176 @@ pub fn batch_generate_ring_vrf(
     .into()
 }
 
+#[napi]
+pub fn batch_generate_ring_vrf_with_aux(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    secret_seed: Buffer,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+    aux_data: Buffer,
+) -> Buffer {
+    ffi::batch_generate_ring_vrf_with_aux(
+        ring_keys.as_ref(),
+        prover_key_index,
+        secret_seed.as_ref(),
+        inputs_data.as_ref(),
+        vrf_input_data_len,
+        aux_data.as_ref(),
+    )
+    .into()
+}
+
+#[napi]
+pub fn vrf_output_from_signature(signature: Buffer) -> Buffer {
+    ffi::vrf_output_from_signature(signature.as_ref()).into()
+}
+
+#[napi]
+pub fn vrf_output_full_hash_from_signature(signature: Buffer) -> Buffer {
+    ffi::vrf_output_full_hash_from_signature(signature.as_ref()).into()
+}
+
+#[napi]
+pub fn vrf_output_hashes(
+    secret_seed: Buffer,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+) -> Buffer {
+    ffi::vrf_output_hashes(
+        secret_seed.as_ref(),
+        inputs_data.as_ref(),
+        vrf_input_data_len,
+    )
+    .into()
+}
+
+#[napi]
+pub fn batch_generate_ring_vrf_below_threshold(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    secret_seed: Buffer,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+    threshold: Buffer,
+    aux_data: Buffer,
+) -> Buffer {
+    ffi::batch_generate_ring_vrf_below_threshold(
+        ring_keys.as_ref(),
+        prover_key_index,
+        secret_seed.as_ref(),
+        inputs_data.as_ref(),
+        vrf_input_data_len,
+        threshold.as_ref(),
+        aux_data.as_ref(),
+    )
+    .into()
+}
+
+#[napi]
+pub fn import_secret_seed(seed: Buffer) -> Buffer {
+    ffi::import_secret_seed(seed.as_ref()).into()
+}
+
+#[napi]
+pub fn import_secret_scalar(scalar: Buffer) -> Buffer {
+    ffi::import_secret_scalar(scalar.as_ref()).into()
+}
+
+#[napi]
+pub fn export_secret_scalar(key: u32) -> Buffer {
+    ffi::export_secret_scalar(key).into()
+}
+
+#[napi]
+pub fn secret_key_public(key: u32) -> Buffer {
+    ffi::secret_key_public(key).into()
+}
+
+#[napi]
+pub fn release_secret_key(key: u32) -> Buffer {
+    ffi::release_secret_key(key).into()
+}
+
+#[napi]
+pub fn import_keystore(file: Buffer, passphrase: Buffer) -> Buffer {
+    ffi::import_keystore(file.as_ref(), passphrase.as_ref()).into()
+}
+
+#[napi]
+pub fn export_keystore(keys: Buffer, passphrase: Buffer) -> Buffer {
+    ffi::export_keystore(keys.as_ref(), passphrase.as_ref()).into()
+}
+
+#[napi]
+pub fn generate_header_seals_with_key(
+    key: u32,
+    seal_payload: Buffer,
+    unsealed_header: Buffer,
+    entropy_prefix: Buffer,
+) -> Buffer {
+    ffi::generate_header_seals_with_key(
+        key,
+        seal_payload.as_ref(),
+        unsealed_header.as_ref(),
+        entropy_prefix.as_ref(),
+    )
+    .into()
+}
+
+#[napi]
+pub fn generate_seal_with_key(key: u32, input: Buffer, aux_data: Buffer) -> Buffer {
+    ffi::generate_seal_with_key(key, input.as_ref(), aux_data.as_ref()).into()
+}
+
+#[napi]
+pub fn vrf_output_hash_with_key(key: u32, input: Buffer) -> Buffer {
+    ffi::vrf_output_hash_with_key(key, input.as_ref()).into()
+}
+
+#[napi]
+pub fn generate_pedersen_vrf_with_key(key: u32, input: Buffer, aux_data: Buffer) -> Buffer {
+    ffi::generate_pedersen_vrf_with_key(key, input.as_ref(), aux_data.as_ref()).into()
+}
+
+#[napi]
+pub fn generate_ring_vrf_with_key(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    key: u32,
+    vrf_input_data: Buffer,
+    aux_data: Buffer,
+) -> Buffer {
+    ffi::generate_ring_vrf_with_key(
+        ring_keys.as_ref(),
+        prover_key_index,
+        key,
+        vrf_input_data.as_ref(),
+        aux_data.as_ref(),
+    )
+    .into()
+}
+
+#[napi]
+pub fn batch_generate_ring_vrf_with_key(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    key: u32,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+    aux_data: Buffer,
+) -> Buffer {
+    ffi::batch_generate_ring_vrf_with_key(
+        ring_keys.as_ref(),
+        prover_key_index,
+        key,
+        inputs_data.as_ref(),
+        vrf_input_data_len,
+        aux_data.as_ref(),
+    )
+    .into()
+}
+
 #[napi]
 pub fn batch_generate_ring_vrf_for_validators(
     ring_keys: Buffer,
@@ -112,6 +416,28 @@ pub fn batch_generate_ring_vrf_for_validators(
     .into()
 }
 
+#[napi]
+pub fn batch_generate_ring_vrf_for_validators_with_aux(
+    ring_keys: Buffer,
+    prover_key_indices: Buffer,
+    secret_seeds_data: Buffer,
+    secret_seed_data_len: u32,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+    aux_data: Buffer,
+) -> Buffer {
+    ffi::batch_generate_ring_vrf_for_validators_with_aux(
+        ring_keys.as_ref(),
+        prover_key_indices.as_ref(),
+        secret_seeds_data.as_ref(),
+        secret_seed_data_len,
+        inputs_data.as_ref(),
+        vrf_input_data_len,
+        aux_data.as_ref(),
+    )
+    .into()
+}
+
 #[napi]
 pub fn batch_verify_tickets(
     ring_size: u32,
@@ -127,3 +453,319 @@ pub fn batch_verify_tickets(
     )
     .into()
 }
+
+#[napi]
+pub fn batch_verify_tickets_with_aux(
+    ring_size: u32,
+    commitment: Buffer,
+    tickets_data: Buffer,
+    vrf_input_data_len: u32,
+) -> Buffer {
+    ffi::batch_verify_tickets_with_aux(
+        ring_size,
+        commitment.as_ref(),
+        tickets_data.as_ref(),
+        vrf_input_data_len,
+    )
+    .into()
+}
+
+/// Runs an `ffi` call on the libuv thread pool instead of the main thread.
+///
+/// The `Buffer` arguments are copied before scheduling, since they can't be
+/// shared with the worker thread. Copies of secrets are wiped on drop.
+pub struct FfiTask(Option<Box<dyn FnOnce() -> Vec<u8> + Send>>);
+
+impl FfiTask {
+    fn new(call: impl FnOnce() -> Vec<u8> + Send + 'static) -> AsyncTask<Self> {
+        AsyncTask::new(Self(Some(Box::new(call))))
+    }
+}
+
+impl Task for FfiTask {
+    type Output = Vec<u8>;
+    type JsValue = Buffer;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let call = self
+            .0
+            .take()
+            .ok_or_else(|| Error::from_reason("Task already computed"))?;
+        Ok(call())
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+#[napi]
+pub fn ring_commitment_async(keys: Buffer) -> AsyncTask<FfiTask> {
+    let keys = keys.to_vec();
+    FfiTask::new(move || ffi::ring_commitment(&keys))
+}
+
+#[napi]
+pub fn ring_commitment_with_offenders_async(
+    keys: Buffer,
+    offender_indices: Buffer,
+) -> AsyncTask<FfiTask> {
+    let keys = keys.to_vec();
+    let offender_indices = offender_indices.to_vec();
+    FfiTask::new(move || ffi::ring_commitment_with_offenders(&keys, &offender_indices))
+}
+
+#[napi]
+pub fn ring_commitment_with_ed25519_offenders_async(
+    keys: Buffer,
+    ed25519_keys: Buffer,
+    offenders: Buffer,
+) -> AsyncTask<FfiTask> {
+    let keys = keys.to_vec();
+    let ed25519_keys = ed25519_keys.to_vec();
+    let offenders = offenders.to_vec();
+    FfiTask::new(move || {
+        ffi::ring_commitment_with_ed25519_offenders(&keys, &ed25519_keys, &offenders)
+    })
+}
+
+#[napi]
+pub fn update_ring_commitment_async(
+    previous_keys: Buffer,
+    previous_commitment: Buffer,
+    replacements: Buffer,
+) -> AsyncTask<FfiTask> {
+    let previous_keys = previous_keys.to_vec();
+    let previous_commitment = previous_commitment.to_vec();
+    let replacements = replacements.to_vec();
+    FfiTask::new(move || {
+        ffi::update_ring_commitment(&previous_keys, &previous_commitment, &replacements)
+    })
+}
+
+#[napi]
+pub fn generate_ring_vrf_async(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    secret_seed: Buffer,
+    vrf_input_data: Buffer,
+) -> AsyncTask<FfiTask> {
+    let ring_keys = ring_keys.to_vec();
+    let secret_seed = Zeroizing::new(secret_seed.to_vec());
+    let vrf_input_data = vrf_input_data.to_vec();
+    FfiTask::new(move || {
+        ffi::generate_ring_vrf(&ring_keys, prover_key_index, &secret_seed, &vrf_input_data)
+    })
+}
+
+#[napi]
+pub fn generate_ring_vrf_with_aux_async(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    secret_seed: Buffer,
+    vrf_input_data: Buffer,
+    aux_data: Buffer,
+) -> AsyncTask<FfiTask> {
+    let ring_keys = ring_keys.to_vec();
+    let secret_seed = Zeroizing::new(secret_seed.to_vec());
+    let vrf_input_data = vrf_input_data.to_vec();
+    let aux_data = aux_data.to_vec();
+    FfiTask::new(move || {
+        ffi::generate_ring_vrf_with_aux(
+            &ring_keys,
+            prover_key_index,
+            &secret_seed,
+            &vrf_input_data,
+            &aux_data,
+        )
+    })
+}
+
+#[napi]
+pub fn generate_ring_vrf_for_secret_async(
+    ring_keys: Buffer,
+    secret_seed: Buffer,
+    vrf_input_data: Buffer,
+    aux_data: Buffer,
+) -> AsyncTask<FfiTask> {
+    let ring_keys = ring_keys.to_vec();
+    let secret_seed = Zeroizing::new(secret_seed.to_vec());
+    let vrf_input_data = vrf_input_data.to_vec();
+    let aux_data = aux_data.to_vec();
+    FfiTask::new(move || {
+        ffi::generate_ring_vrf_for_secret(&ring_keys, &secret_seed, &vrf_input_data, &aux_data)
+    })
+}
+
+#[napi]
+pub fn batch_generate_ring_vrf_async(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    secret_seed: Buffer,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+) -> AsyncTask<FfiTask> {
+    let ring_keys = ring_keys.to_vec();
+    let secret_seed = Zeroizing::new(secret_seed.to_vec());
+    let inputs_data = inputs_data.to_vec();
+    FfiTask::new(move || {
+        ffi::batch_generate_ring_vrf(
+            &ring_keys,
+            prover_key_index,
+            &secret_seed,
+            &inputs_data,
+            vrf_input_data_len,
+        )
+    })
+}
+
+#[napi]
+pub fn batch_generate_ring_vrf_with_aux_async(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    secret_seed: Buffer,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+    aux_data: Buffer,
+) -> AsyncTask<FfiTask> {
+    let ring_keys = ring_keys.to_vec();
+    let secret_seed = Zeroizing::new(secret_seed.to_vec());
+    let inputs_data = inputs_data.to_vec();
+    let aux_data = aux_data.to_vec();
+    FfiTask::new(move || {
+        ffi::batch_generate_ring_vrf_with_aux(
+            &ring_keys,
+            prover_key_index,
+            &secret_seed,
+            &inputs_data,
+            vrf_input_data_len,
+            &aux_data,
+        )
+    })
+}
+
+#[napi]
+pub fn batch_generate_ring_vrf_below_threshold_async(
+    ring_keys: Buffer,
+    prover_key_index: u32,
+    secret_seed: Buffer,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+    threshold: Buffer,
+    aux_data: Buffer,
+) -> AsyncTask<FfiTask> {
+    let ring_keys = ring_keys.to_vec();
+    let secret_seed = Zeroizing::new(secret_seed.to_vec());
+    let inputs_data = inputs_data.to_vec();
+    let threshold = threshold.to_vec();
+    let aux_data = aux_data.to_vec();
+    FfiTask::new(move || {
+        ffi::batch_generate_ring_vrf_below_threshold(
+            &ring_keys,
+            prover_key_index,
+            &secret_seed,
+            &inputs_data,
+            vrf_input_data_len,
+            &threshold,
+            &aux_data,
+        )
+    })
+}
+
+#[napi]
+pub fn batch_generate_ring_vrf_for_validators_async(
+    ring_keys: Buffer,
+    prover_key_indices: Buffer,
+    secret_seeds_data: Buffer,
+    secret_seed_data_len: u32,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+) -> AsyncTask<FfiTask> {
+    let ring_keys = ring_keys.to_vec();
+    let prover_key_indices = prover_key_indices.to_vec();
+    let secret_seeds_data = Zeroizing::new(secret_seeds_data.to_vec());
+    let inputs_data = inputs_data.to_vec();
+    FfiTask::new(move || {
+        ffi::batch_generate_ring_vrf_for_validators(
+            &ring_keys,
+            &prover_key_indices,
+            &secret_seeds_data,
+            secret_seed_data_len,
+            &inputs_data,
+            vrf_input_data_len,
+        )
+    })
+}
+
+#[napi]
+pub fn batch_generate_ring_vrf_for_validators_with_aux_async(
+    ring_keys: Buffer,
+    prover_key_indices: Buffer,
+    secret_seeds_data: Buffer,
+    secret_seed_data_len: u32,
+    inputs_data: Buffer,
+    vrf_input_data_len: u32,
+    aux_data: Buffer,
+) -> AsyncTask<FfiTask> {
+    let ring_keys = ring_keys.to_vec();
+    let prover_key_indices = prover_key_indices.to_vec();
+    let secret_seeds_data = Zeroizing::new(secret_seeds_data.to_vec());
+    let inputs_data = inputs_data.to_vec();
+    let aux_data = aux_data.to_vec();
+    FfiTask::new(move || {
+        ffi::batch_generate_ring_vrf_for_validators_with_aux(
+            &ring_keys,
+            &prover_key_indices,
+            &secret_seeds_data,
+            secret_seed_data_len,
+            &inputs_data,
+            vrf_input_data_len,
+            &aux_data,
+        )
+    })
+}
+
+#[napi]
+pub fn batch_verify_header_seals_async(headers_data: Buffer) -> AsyncTask<FfiTask> {
+    let headers_data = headers_data.to_vec();
+    FfiTask::new(move || ffi::batch_verify_header_seals(&headers_data))
+}
+
+#[napi]
+pub fn batch_ietf_vrf_verify_async(signatures_data: Buffer) -> AsyncTask<FfiTask> {
+    let signatures_data = signatures_data.to_vec();
+    FfiTask::new(move || ffi::batch_ietf_vrf_verify(&signatures_data))
+}
+
+#[napi]
+pub fn batch_verify_tickets_async(
+    ring_size: u32,
+    commitment: Buffer,
+    tickets_data: Buffer,
+    vrf_input_data_len: u32,
+) -> AsyncTask<FfiTask> {
+    let commitment = commitment.to_vec();
+    let tickets_data = tickets_data.to_vec();
+    FfiTask::new(move || {
+        ffi::batch_verify_tickets(ring_size, &commitment, &tickets_data, vrf_input_data_len)
+    })
+}
+
+#[napi]
+pub fn batch_verify_tickets_with_aux_async(
+    ring_size: u32,
+    commitment: Buffer,
+    tickets_data: Buffer,
+    vrf_input_data_len: u32,
+) -> AsyncTask<FfiTask> {
+    let commitment = commitment.to_vec();
+    let tickets_data = tickets_data.to_vec();
+    FfiTask::new(move || {
+        ffi::batch_verify_tickets_with_aux(
+            ring_size,
+            &commitment,
+            &tickets_data,
+            vrf_input_data_len,
+        )
+    })
+}
@@ -38,7 +38,22 @@ async function loadWasmBinding(
 
 export type BandersnatchApi = {
   isNativeBinding: () => boolean;
+  initPcsParams: (bytes: Uint8Array) => Uint8Array;
+  serializeRingParams: (ringSize: number, commitment: Uint8Array) => Uint8Array;
+  loadRingParams: (snapshot: Uint8Array) => Uint8Array;
   ringCommitment: (keys: Uint8Array) => Uint8Array;
+  ringCommitmentWithOffenders: (keys: Uint8Array, offenderIndices: Uint8Array) => Uint8Array;
+  ringCommitmentWithEd25519Offenders: (
+    keys: Uint8Array,
+    ed25519Keys: Uint8Array,
+    offenders: Uint8Array
+  ) => Uint8Array;
+  updateRingCommitment: (
+    previousKeys: Uint8Array,
+    previousCommitment: Uint8Array,
+    replacements: Uint8Array
+  ) => Uint8Array;
+  clearRingCache: () => void;
   derivePublicKey: (seed: Uint8Array) => Uint8Array;
   verifyHeaderSeals: (
     signerKey: Uint8Array,
@@ -48,26 +63,101 @@ export type BandersnatchApi = {
     entropyData: Uint8Array,
     entropyPrefix: Uint8Array
   ) => Uint8Array;
+  batchVerifyHeaderSeals: (headersData: Uint8Array) => Uint8Array;
+  batchIetfVrfVerify: (signaturesData: Uint8Array) => Uint8Array;
   verifySeal: (
     signerKey: Uint8Array,
     sealData: Uint8Array,
     payload: Uint8Array,
     auxData: Uint8Array
   ) => Uint8Array;
+  generateHeaderSeals: (
+    secretSeed: Uint8Array,
+    sealPayload: Uint8Array,
+    unsealedHeader: Uint8Array,
+    entropyPrefix: Uint8Array
+  ) => Uint8Array;
   generateSeal: (secretSeed: Uint8Array, input: Uint8Array, auxData: Uint8Array) => Uint8Array;
+  generatePedersenVrf: (
+    secretSeed: Uint8Array,
+    input: Uint8Array,
+    auxData: Uint8Array
+  ) => Uint8Array;
+  verifyPedersenVrf: (input: Uint8Array, auxData: Uint8Array, signature: Uint8Array) => Uint8Array;
+  pedersenKeyCommitment: (publicKey: Uint8Array, blinding: Uint8Array) => Uint8Array;
   vrfOutputHash: (secretSeed: Uint8Array, input: Uint8Array) => Uint8Array;
+  vrfOutputFromSignature: (signature: Uint8Array) => Uint8Array;
+  vrfOutputFullHashFromSignature: (signature: Uint8Array) => Uint8Array;
+  vrfOutputHashes: (
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Uint8Array;
   generateRingVrf: (
     ringKeys: Uint8Array,
     proverKeyIndex: number,
     secretSeed: Uint8Array,
-    vrfInputData: Uint8Array
+    vrfInputData: Uint8Array,
+    auxData?: Uint8Array
+  ) => Uint8Array;
+  locateProverKeyIndex: (ringKeys: Uint8Array, secretSeed: Uint8Array) => Uint8Array;
+  generateRingVrfForSecret: (
+    ringKeys: Uint8Array,
+    secretSeed: Uint8Array,
+    vrfInputData: Uint8Array,
+    auxData?: Uint8Array
   ) => Uint8Array;
   batchGenerateRingVrf: (
     ringKeys: Uint8Array,
     proverKeyIndex: number,
     secretSeed: Uint8Array,
     inputsData: Uint8Array,
-    vrfInputDataLen: number
+    vrfInputDataLen: number,
+    auxData?: Uint8Array
+  ) => Uint8Array;
+  batchGenerateRingVrfBelowThreshold: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    threshold: Uint8Array,
+    auxData?: Uint8Array
+  ) => Uint8Array;
+  importSecretSeed: (seed: Uint8Array) => Uint8Array;
+  importSecretScalar: (scalar: Uint8Array) => Uint8Array;
+  exportSecretScalar: (key: number) => Uint8Array;
+  secretKeyPublic: (key: number) => Uint8Array;
+  releaseSecretKey: (key: number) => Uint8Array;
+  importKeystore: (file: Uint8Array, passphrase: Uint8Array) => Uint8Array;
+  exportKeystore: (keys: Uint8Array, passphrase: Uint8Array) => Uint8Array;
+  generateHeaderSealsWithKey: (
+    key: number,
+    sealPayload: Uint8Array,
+    unsealedHeader: Uint8Array,
+    entropyPrefix: Uint8Array
+  ) => Uint8Array;
+  generateSealWithKey: (key: number, input: Uint8Array, auxData: Uint8Array) => Uint8Array;
+  vrfOutputHashWithKey: (key: number, input: Uint8Array) => Uint8Array;
+  generatePedersenVrfWithKey: (
+    key: number,
+    input: Uint8Array,
+    auxData: Uint8Array
+  ) => Uint8Array;
+  generateRingVrfWithKey: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    key: number,
+    vrfInputData: Uint8Array,
+    auxData?: Uint8Array
+  ) => Uint8Array;
+  batchGenerateRingVrfWithKey: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    key: number,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    auxData?: Uint8Array
   ) => Uint8Array;
   batchGenerateRingVrfForValidators: (
     ringKeys: Uint8Array,
@@ -75,7 +165,8 @@ export type BandersnatchApi = {
     secretSeedsData: Uint8Array,
     secretSeedDataLen: number,
     inputsData: Uint8Array,
-    vrfInputDataLen: number
+    vrfInputDataLen: number,
+    auxData?: Uint8Array
   ) => Uint8Array;
   batchVerifyTickets: (
     ringSize: number,
@@ -83,26 +174,152 @@ export type BandersnatchApi = {
     ticketsData: Uint8Array,
     vrfInputDataLen: number
   ) => Uint8Array;
+  batchVerifyTicketsWithAux: (
+    ringSize: number,
+    commitment: Uint8Array,
+    ticketsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Uint8Array;
+  ringCommitmentAsync: (keys: Uint8Array) => Promise<Uint8Array>;
+  ringCommitmentWithOffendersAsync: (
+    keys: Uint8Array,
+    offenderIndices: Uint8Array
+  ) => Promise<Uint8Array>;
+  ringCommitmentWithEd25519OffendersAsync: (
+    keys: Uint8Array,
+    ed25519Keys: Uint8Array,
+    offenders: Uint8Array
+  ) => Promise<Uint8Array>;
+  updateRingCommitmentAsync: (
+    previousKeys: Uint8Array,
+    previousCommitment: Uint8Array,
+    replacements: Uint8Array
+  ) => Promise<Uint8Array>;
+  generateRingVrfAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    vrfInputData: Uint8Array,
+    auxData?: Uint8Array
+  ) => Promise<Uint8Array>;
+  generateRingVrfForSecretAsync: (
+    ringKeys: Uint8Array,
+    secretSeed: Uint8Array,
+    vrfInputData: Uint8Array,
+    auxData?: Uint8Array
+  ) => Promise<Uint8Array>;
+  batchGenerateRingVrfAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    auxData?: Uint8Array
+  ) => Promise<Uint8Array>;
+  batchGenerateRingVrfBelowThresholdAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    threshold: Uint8Array,
+    auxData?: Uint8Array
+  ) => Promise<Uint8Array>;
+  batchGenerateRingVrfForValidatorsAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndices: Uint32Array | readonly number[],
+    secretSeedsData: Uint8Array,
+    secretSeedDataLen: number,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    auxData?: Uint8Array
+  ) => Promise<Uint8Array>;
+  batchVerifyHeaderSealsAsync: (headersData: Uint8Array) => Promise<Uint8Array>;
+  batchIetfVrfVerifyAsync: (signaturesData: Uint8Array) => Promise<Uint8Array>;
+  batchVerifyTicketsAsync: (
+    ringSize: number,
+    commitment: Uint8Array,
+    ticketsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Promise<Uint8Array>;
+  batchVerifyTicketsWithAuxAsync: (
+    ringSize: number,
+    commitment: Uint8Array,
+    ticketsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Promise<Uint8Array>;
 };
 
 function createApi(): BandersnatchApi {
   return {
     isNativeBinding,
+    initPcsParams,
+    serializeRingParams,
+    loadRingParams,
     ringCommitment,
+    ringCommitmentWithOffenders,
+    ringCommitmentWithEd25519Offenders,
+    updateRingCommitment,
+    clearRingCache,
     derivePublicKey,
     verifyHeaderSeals,
+    batchVerifyHeaderSeals,
+    batchIetfVrfVerify,
     verifySeal,
+    generateHeaderSeals,
     generateSeal,
+    generatePedersenVrf,
+    verifyPedersenVrf,
+    pedersenKeyCommitment,
     vrfOutputHash,
+    vrfOutputFromSignature,
+    vrfOutputFullHashFromSignature,
+    vrfOutputHashes,
     generateRingVrf,
+    locateProverKeyIndex,
+    generateRingVrfForSecret,
     batchGenerateRingVrf,
+    batchGenerateRingVrfBelowThreshold,
+    importSecretSeed,
+    importSecretScalar,
+    exportSecretScalar,
+    secretKeyPublic,
+    releaseSecretKey,
+    importKeystore,
+    exportKeystore,
+    generateHeaderSealsWithKey,
+    generateSealWithKey,
+    vrfOutputHashWithKey,
+    generatePedersenVrfWithKey,
+    generateRingVrfWithKey,
+    batchGenerateRingVrfWithKey,
     batchGenerateRingVrfForValidators,
     batchVerifyTickets,
+    batchVerifyTicketsWithAux,
+    ringCommitmentAsync,
+    ringCommitmentWithOffendersAsync,
+    ringCommitmentWithEd25519OffendersAsync,
+    updateRingCommitmentAsync,
+    generateRingVrfAsync,
+    generateRingVrfForSecretAsync,
+    batchGenerateRingVrfAsync,
+    batchGenerateRingVrfBelowThresholdAsync,
+    batchGenerateRingVrfForValidatorsAsync,
+    batchVerifyHeaderSealsAsync,
+    batchIetfVrfVerifyAsync,
+    batchVerifyTicketsAsync,
+    batchVerifyTicketsWithAuxAsync,
   };
 }
 
 export type InitOptions = {
   module_or_path?: WasmBinding.InitInput | Promise<WasmBinding.InitInput>;
+  /**
+   * PCS (KZG SRS) parameters to use for ring proofs instead of the embedded
+   * ones (compressed or uncompressed). Required if the binding was built
+   * without the embedded SRS.
+   */
+  pcsParams?: Uint8Array;
 };
 
 export default async function init(options?: InitOptions): Promise<BandersnatchApi> {
@@ -113,10 +330,17 @@ export default async function init(options?: InitOptions): Promise<BandersnatchA
   const native = await loadNativeBinding();
   if (native) {
     nativeBinding = native;
-    return createApi();
+  } else {
+    wasmBinding = await loadWasmBinding(options?.module_or_path);
+  }
+
+  if (options?.pcsParams !== undefined) {
+    const result = initPcsParams(options.pcsParams);
+    if (result[0] !== 0) {
+      throw new Error("Invalid PCS params or PCS params already initialized.");
+    }
   }
 
-  wasmBinding = await loadWasmBinding(options?.module_or_path);
   return createApi();
 }
 
@@ -147,6 +371,52 @@ function assertInitialized(): void {
   }
 }
 
+/**
+ * Supply PCS (KZG SRS) parameters for ring proofs.
+ *
+ * Must be called before any ring operation. Returns a single status byte:
+ * `0x00` on success, `0x01` if the params are invalid or were already set.
+ */
+export function initPcsParams(bytes: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.initPcsParams(bytes);
+  }
+  return wasmBinding!.init_pcs_params(bytes);
+}
+
+/**
+ * Serialize derived ring proof parameters, to warm-start from a cache file.
+ *
+ * If `commitment` is non-empty, the verifier key of that ring is included too.
+ * Returns `status byte || snapshot`.
+ */
+export function serializeRingParams(ringSize: number, commitment: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.serializeRingParams(ringSize, commitment);
+  }
+  return wasmBinding!.serialize_ring_params(ringSize, commitment);
+}
+
+/**
+ * Load ring proof parameters from a snapshot created by `serializeRingParams`.
+ *
+ * Must be called before any ring operation for that ring size. The snapshot is
+ * not validated beyond a corruption check, so it must come from a trusted
+ * location. It is rejected if it was derived from other PCS parameters than
+ * the ones in use (see `initPcsParams`). Returns a single status byte: `0x00`
+ * on success, `0x01` if the snapshot is corrupted, incompatible or the
+ * parameters are already in use.
+ */
+export function loadRingParams(snapshot: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.loadRingParams(snapshot);
+  }
+  return wasmBinding!.load_ring_params(snapshot);
+}
+
 export function ringCommitment(keys: Uint8Array): Uint8Array {
   assertInitialized();
   if (nativeBinding) {
@@ -155,6 +425,77 @@ export function ringCommitment(keys: Uint8Array): Uint8Array {
   return wasmBinding!.ring_commitment(keys);
 }
 
+/**
+ * Ring commitment with the keys of offenders replaced by the padding point,
+ * as the Gray Paper's `Φ` function does before the ring is committed to.
+ *
+ * `offenderIndices` are the offenders' positions in `keys` (concatenated
+ * u32 LE). Returns `status || commitment`.
+ */
+export function ringCommitmentWithOffenders(
+  keys: Uint8Array,
+  offenderIndices: Uint8Array
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.ringCommitmentWithOffenders(keys, offenderIndices);
+  }
+  return wasmBinding!.ring_commitment_with_offenders(keys, offenderIndices);
+}
+
+/**
+ * Same as `ringCommitmentWithOffenders`, with offenders given by their ed25519
+ * keys: `ed25519Keys` holds the ed25519 key of each ring member, in the order
+ * of `keys`, and `offenders` the concatenated ed25519 keys of the offenders.
+ */
+export function ringCommitmentWithEd25519Offenders(
+  keys: Uint8Array,
+  ed25519Keys: Uint8Array,
+  offenders: Uint8Array
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.ringCommitmentWithEd25519Offenders(keys, ed25519Keys, offenders);
+  }
+  return wasmBinding!.ring_commitment_with_ed25519_offenders(keys, ed25519Keys, offenders);
+}
+
+/**
+ * Ring commitment of `previousKeys` with some keys replaced, computed from
+ * `previousCommitment` (the commitment to `previousKeys`) instead of from
+ * scratch, for rings that change only slightly between epochs.
+ *
+ * `replacements` is laid out as `(index (u32 LE) || key (32 bytes))*`, applied
+ * in order. Returns `status || commitment`, like `ringCommitment` of the
+ * updated keys.
+ */
+export function updateRingCommitment(
+  previousKeys: Uint8Array,
+  previousCommitment: Uint8Array,
+  replacements: Uint8Array
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.updateRingCommitment(previousKeys, previousCommitment, replacements);
+  }
+  return wasmBinding!.update_ring_commitment(previousKeys, previousCommitment, replacements);
+}
+
+/**
+ * Drop ring data (commitments, prover keys, verifiers) cached between calls.
+ *
+ * The cache is bounded, but clearing it after an epoch change releases the
+ * memory held by rings that won't be used anymore.
+ */
+export function clearRingCache(): void {
+  assertInitialized();
+  if (nativeBinding) {
+    nativeBinding.clearRingCache();
+    return;
+  }
+  wasmBinding!.clear_ring_cache();
+}
+
 export function derivePublicKey(seed: Uint8Array): Uint8Array {
   assertInitialized();
   if (nativeBinding) {
@@ -192,6 +533,41 @@ export function verifyHeaderSeals(
   );
 }
 
+/**
+ * Verify the seals of many headers in one call, as `verifyHeaderSeals` would.
+ *
+ * Each header is laid out as `signerKey (32) || seal (96) || entropy (96)`,
+ * followed by `sealPayload`, `unsealedHeader` and `entropyPrefix`, each
+ * prefixed with its length (u32 LE).
+ *
+ * Returns `(status || sealHash (32) || entropyHash (32))*`, one per header,
+ * or just the `0x01` status if the layout is malformed.
+ */
+export function batchVerifyHeaderSeals(headersData: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchVerifyHeaderSeals(headersData);
+  }
+  return wasmBinding!.batch_verify_header_seals(headersData);
+}
+
+/**
+ * Verify many seals in one call, as `verifySeal` would.
+ *
+ * Each seal is laid out as `signerKey (32) || seal (96)`, followed by
+ * `payload` and `auxData`, each prefixed with its length (u32 LE).
+ *
+ * Returns `(status || outputHash (32))*`, one per seal, or just the `0x01`
+ * status if the layout is malformed.
+ */
+export function batchIetfVrfVerify(signaturesData: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchIetfVrfVerify(signaturesData);
+  }
+  return wasmBinding!.batch_ietf_vrf_verify(signaturesData);
+}
+
 export function verifySeal(
   signerKey: Uint8Array,
   sealData: Uint8Array,
@@ -205,6 +581,36 @@ export function verifySeal(
   return wasmBinding!.verify_seal(signerKey, sealData, payload, auxData);
 }
 
+/**
+ * Generate the header seal and the entropy source signature, the counterpart
+ * of `verifyHeaderSeals`. The entropy payload (`entropyPrefix || seal output`)
+ * is built internally.
+ *
+ * Returns `status || seal (96 bytes) || entropy (96 bytes)`.
+ */
+export function generateHeaderSeals(
+  secretSeed: Uint8Array,
+  sealPayload: Uint8Array,
+  unsealedHeader: Uint8Array,
+  entropyPrefix: Uint8Array
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.generateHeaderSeals(
+      secretSeed,
+      sealPayload,
+      unsealedHeader,
+      entropyPrefix
+    );
+  }
+  return wasmBinding!.generate_header_seals(
+    secretSeed,
+    sealPayload,
+    unsealedHeader,
+    entropyPrefix
+  );
+}
+
 export function generateSeal(
   secretSeed: Uint8Array,
   input: Uint8Array,
@@ -217,6 +623,57 @@ export function generateSeal(
   return wasmBinding!.generate_seal(secretSeed, input, auxData);
 }
 
+/**
+ * Generate a Pedersen VRF signature, proving the output against a blinded
+ * public key instead of revealing it.
+ *
+ * Returns `status || signature (192 bytes) || blinding (32 bytes)`. The
+ * blinding factor opens the key commitment, see `pedersenKeyCommitment`.
+ */
+export function generatePedersenVrf(
+  secretSeed: Uint8Array,
+  input: Uint8Array,
+  auxData: Uint8Array
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.generatePedersenVrf(secretSeed, input, auxData);
+  }
+  return wasmBinding!.generate_pedersen_vrf(secretSeed, input, auxData);
+}
+
+/**
+ * Verify a Pedersen VRF signature.
+ *
+ * Returns `status || vrfOutputHash (32 bytes) || keyCommitment (32 bytes)`,
+ * zero-filled on failure.
+ */
+export function verifyPedersenVrf(
+  input: Uint8Array,
+  auxData: Uint8Array,
+  signature: Uint8Array
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.verifyPedersenVrf(input, auxData, signature);
+  }
+  return wasmBinding!.verify_pedersen_vrf(input, auxData, signature);
+}
+
+/**
+ * Compute the blinded key commitment for a public key and blinding factor.
+ *
+ * Matches the commitment returned by `verifyPedersenVrf` if the signature was
+ * generated by the owner of `publicKey`. Returns `status || keyCommitment (32 bytes)`.
+ */
+export function pedersenKeyCommitment(publicKey: Uint8Array, blinding: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.pedersenKeyCommitment(publicKey, blinding);
+  }
+  return wasmBinding!.pedersen_key_commitment(publicKey, blinding);
+}
+
 export function vrfOutputHash(secretSeed: Uint8Array, input: Uint8Array): Uint8Array {
   assertInitialized();
   if (nativeBinding) {
@@ -225,24 +682,115 @@ export function vrfOutputHash(secretSeed: Uint8Array, input: Uint8Array): Uint8A
   return wasmBinding!.vrf_output_hash(secretSeed, input);
 }
 
+/**
+ * VRF output hash of an IETF or ring signature, without verifying it.
+ *
+ * Meant for signatures that were already verified, e.g. to recompute entropy
+ * after a reorg. Returns `status || hash (32 bytes)`.
+ */
+export function vrfOutputFromSignature(signature: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.vrfOutputFromSignature(signature);
+  }
+  return wasmBinding!.vrf_output_from_signature(signature);
+}
+
+/**
+ * Like `vrfOutputFromSignature`, but returns the full `status || hash (64 bytes)`.
+ */
+export function vrfOutputFullHashFromSignature(signature: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.vrfOutputFullHashFromSignature(signature);
+  }
+  return wasmBinding!.vrf_output_full_hash_from_signature(signature);
+}
+
+/**
+ * VRF output hashes (ticket ids) of consecutive inputs of `vrfInputDataLen` bytes.
+ *
+ * Much cheaper than generating ring proofs, so it can be used to pick the
+ * attempts worth proving. Returns `(status || hash (32 bytes))*`, one per input.
+ */
+export function vrfOutputHashes(
+  secretSeed: Uint8Array,
+  inputsData: Uint8Array,
+  vrfInputDataLen: number
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.vrfOutputHashes(secretSeed, inputsData, vrfInputDataLen);
+  }
+  return wasmBinding!.vrf_output_hashes(secretSeed, inputsData, vrfInputDataLen);
+}
+
 /**
  * Generate one ring VRF ticket for a concrete VRF input.
  *
  * This is the single-attempt form of `batchGenerateRingVrf`: callers encode the
  * desired attempt into `vrfInputData` and receive one `status || signature`
  * record, where the signature is 784 bytes.
+ *
+ * `auxData` (empty by default, as for Safrole tickets) is bound into the proof
+ * and must be supplied again on verification. The batch variants bind the same
+ * `auxData` into every generated signature.
  */
 export function generateRingVrf(
   ringKeys: Uint8Array,
   proverKeyIndex: number,
   secretSeed: Uint8Array,
-  vrfInputData: Uint8Array
+  vrfInputData: Uint8Array,
+  auxData: Uint8Array = new Uint8Array()
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.generateRingVrfWithAux(
+      ringKeys,
+      proverKeyIndex,
+      secretSeed,
+      vrfInputData,
+      auxData
+    );
+  }
+  return wasmBinding!.generate_ring_vrf_with_aux(
+    ringKeys,
+    proverKeyIndex,
+    secretSeed,
+    vrfInputData,
+    auxData
+  );
+}
+
+/**
+ * Position of the secret seed's public key in `ringKeys`.
+ *
+ * Returns `status || index (u32 LE)`; the status is `0x01` if the key is not
+ * in the ring or is there more than once.
+ */
+export function locateProverKeyIndex(ringKeys: Uint8Array, secretSeed: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.locateProverKeyIndex(ringKeys, secretSeed);
+  }
+  return wasmBinding!.locate_prover_key_index(ringKeys, secretSeed);
+}
+
+/**
+ * Same as `generateRingVrf`, but finds the prover's index in `ringKeys` from
+ * its secret seed instead of taking it as an argument.
+ */
+export function generateRingVrfForSecret(
+  ringKeys: Uint8Array,
+  secretSeed: Uint8Array,
+  vrfInputData: Uint8Array,
+  auxData: Uint8Array = new Uint8Array()
 ): Uint8Array {
   assertInitialized();
   if (nativeBinding) {
-    return nativeBinding.generateRingVrf(ringKeys, proverKeyIndex, secretSeed, vrfInputData);
+    return nativeBinding.generateRingVrfForSecret(ringKeys, secretSeed, vrfInputData, auxData);
   }
-  return wasmBinding!.generate_ring_vrf(ringKeys, proverKeyIndex, secretSeed, vrfInputData);
+  return wasmBinding!.generate_ring_vrf_for_secret(ringKeys, secretSeed, vrfInputData, auxData);
 }
 
 export function batchGenerateRingVrf(
@@ -250,24 +798,262 @@ export function batchGenerateRingVrf(
   proverKeyIndex: number,
   secretSeed: Uint8Array,
   inputsData: Uint8Array,
-  vrfInputDataLen: number
+  vrfInputDataLen: number,
+  auxData: Uint8Array = new Uint8Array()
 ): Uint8Array {
   assertInitialized();
   if (nativeBinding) {
-    return nativeBinding.batchGenerateRingVrf(
+    return nativeBinding.batchGenerateRingVrfWithAux(
       ringKeys,
       proverKeyIndex,
       secretSeed,
       inputsData,
-      vrfInputDataLen
+      vrfInputDataLen,
+      auxData
     );
   }
-  return wasmBinding!.batch_generate_ring_vrf(
+  return wasmBinding!.batch_generate_ring_vrf_with_aux(
     ringKeys,
     proverKeyIndex,
     secretSeed,
     inputsData,
-    vrfInputDataLen
+    vrfInputDataLen,
+    auxData
+  );
+}
+
+/**
+ * Score then prove: same as `batchGenerateRingVrf`, but only generating ring
+ * proofs for the inputs whose output hash (ticket id) is below `threshold`
+ * (32 bytes, compared as big-endian numbers).
+ *
+ * Skipped inputs get the `0x02` status with a zeroed signature.
+ */
+export function batchGenerateRingVrfBelowThreshold(
+  ringKeys: Uint8Array,
+  proverKeyIndex: number,
+  secretSeed: Uint8Array,
+  inputsData: Uint8Array,
+  vrfInputDataLen: number,
+  threshold: Uint8Array,
+  auxData: Uint8Array = new Uint8Array()
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchGenerateRingVrfBelowThreshold(
+      ringKeys,
+      proverKeyIndex,
+      secretSeed,
+      inputsData,
+      vrfInputDataLen,
+      threshold,
+      auxData
+    );
+  }
+  return wasmBinding!.batch_generate_ring_vrf_below_threshold(
+    ringKeys,
+    proverKeyIndex,
+    secretSeed,
+    inputsData,
+    vrfInputDataLen,
+    threshold,
+    auxData
+  );
+}
+
+/*
+ * Secret key handles.
+ *
+ * A seed or raw secret scalar is imported once and kept in Rust memory; the
+ * returned key handle (a `u32`) is then used for signing, instead of passing
+ * the seed into every call. Handles must be released with `releaseSecretKey`.
+ */
+
+/** Import a secret key from a seed. Returns `status || keyHandle (u32 LE)`. */
+export function importSecretSeed(seed: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.importSecretSeed(seed);
+  }
+  return wasmBinding!.import_secret_seed(seed);
+}
+
+/**
+ * Import a raw secret scalar (32 bytes, little endian).
+ * Returns `status || keyHandle (u32 LE)`.
+ */
+export function importSecretScalar(scalar: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.importSecretScalar(scalar);
+  }
+  return wasmBinding!.import_secret_scalar(scalar);
+}
+
+/** Export the raw secret scalar of a key. Returns `status || scalar (32 bytes)`. */
+export function exportSecretScalar(key: number): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.exportSecretScalar(key);
+  }
+  return wasmBinding!.export_secret_scalar(key);
+}
+
+/** Public key of an imported key. Returns `status || publicKey (32 bytes)`. */
+export function secretKeyPublic(key: number): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.secretKeyPublic(key);
+  }
+  return wasmBinding!.secret_key_public(key);
+}
+
+/** Forget an imported key. Returns the `0x01` status if the handle is unknown. */
+export function releaseSecretKey(key: number): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.releaseSecretKey(key);
+  }
+  return wasmBinding!.release_secret_key(key);
+}
+
+/**
+ * Import the bandersnatch keys of a passphrase-encrypted keystore file.
+ * Returns `status || (keyHandle (u32 LE) || publicKey (32 bytes))*`.
+ */
+export function importKeystore(file: Uint8Array, passphrase: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.importKeystore(file, passphrase);
+  }
+  return wasmBinding!.import_keystore(file, passphrase);
+}
+
+/**
+ * Seal imported keys (concatenated u32 LE handles) into a keystore file
+ * encrypted with the passphrase. Returns `status || keystoreFile`.
+ */
+export function exportKeystore(keys: Uint8Array, passphrase: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.exportKeystore(keys, passphrase);
+  }
+  return wasmBinding!.export_keystore(keys, passphrase);
+}
+
+/** Same as `generateHeaderSeals`, with an imported key. */
+export function generateHeaderSealsWithKey(
+  key: number,
+  sealPayload: Uint8Array,
+  unsealedHeader: Uint8Array,
+  entropyPrefix: Uint8Array
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.generateHeaderSealsWithKey(
+      key,
+      sealPayload,
+      unsealedHeader,
+      entropyPrefix
+    );
+  }
+  return wasmBinding!.generate_header_seals_with_key(
+    key,
+    sealPayload,
+    unsealedHeader,
+    entropyPrefix
+  );
+}
+
+/** Same as `generateSeal`, with an imported key. */
+export function generateSealWithKey(
+  key: number,
+  input: Uint8Array,
+  auxData: Uint8Array
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.generateSealWithKey(key, input, auxData);
+  }
+  return wasmBinding!.generate_seal_with_key(key, input, auxData);
+}
+
+/** Same as `vrfOutputHash`, with an imported key. */
+export function vrfOutputHashWithKey(key: number, input: Uint8Array): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.vrfOutputHashWithKey(key, input);
+  }
+  return wasmBinding!.vrf_output_hash_with_key(key, input);
+}
+
+/** Same as `generatePedersenVrf`, with an imported key. */
+export function generatePedersenVrfWithKey(
+  key: number,
+  input: Uint8Array,
+  auxData: Uint8Array
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.generatePedersenVrfWithKey(key, input, auxData);
+  }
+  return wasmBinding!.generate_pedersen_vrf_with_key(key, input, auxData);
+}
+
+/** Same as `generateRingVrf`, with an imported key. */
+export function generateRingVrfWithKey(
+  ringKeys: Uint8Array,
+  proverKeyIndex: number,
+  key: number,
+  vrfInputData: Uint8Array,
+  auxData: Uint8Array = new Uint8Array()
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.generateRingVrfWithKey(
+      ringKeys,
+      proverKeyIndex,
+      key,
+      vrfInputData,
+      auxData
+    );
+  }
+  return wasmBinding!.generate_ring_vrf_with_key(
+    ringKeys,
+    proverKeyIndex,
+    key,
+    vrfInputData,
+    auxData
+  );
+}
+
+/** Same as `batchGenerateRingVrf`, with an imported key. */
+export function batchGenerateRingVrfWithKey(
+  ringKeys: Uint8Array,
+  proverKeyIndex: number,
+  key: number,
+  inputsData: Uint8Array,
+  vrfInputDataLen: number,
+  auxData: Uint8Array = new Uint8Array()
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchGenerateRingVrfWithKey(
+      ringKeys,
+      proverKeyIndex,
+      key,
+      inputsData,
+      vrfInputDataLen,
+      auxData
+    );
+  }
+  return wasmBinding!.batch_generate_ring_vrf_with_key(
+    ringKeys,
+    proverKeyIndex,
+    key,
+    inputsData,
+    vrfInputDataLen,
+    auxData
   );
 }
 
@@ -303,27 +1089,30 @@ export function batchGenerateRingVrfForValidators(
   secretSeedsData: Uint8Array,
   secretSeedDataLen: number,
   inputsData: Uint8Array,
-  vrfInputDataLen: number
+  vrfInputDataLen: number,
+  auxData: Uint8Array = new Uint8Array()
 ): Uint8Array {
   assertInitialized();
   const proverKeyIndicesData = encodeProverKeyIndices(proverKeyIndices);
   if (nativeBinding) {
-    return nativeBinding.batchGenerateRingVrfForValidators(
+    return nativeBinding.batchGenerateRingVrfForValidatorsWithAux(
       ringKeys,
       proverKeyIndicesData,
       secretSeedsData,
       secretSeedDataLen,
       inputsData,
-      vrfInputDataLen
+      vrfInputDataLen,
+      auxData
     );
   }
-  return wasmBinding!.batch_generate_ring_vrf_for_validators(
+  return wasmBinding!.batch_generate_ring_vrf_for_validators_with_aux(
     ringKeys,
     proverKeyIndicesData,
     secretSeedsData,
     secretSeedDataLen,
     inputsData,
-    vrfInputDataLen
+    vrfInputDataLen,
+    auxData
   );
 }
 
@@ -357,3 +1146,278 @@ export function batchVerifyTickets(
   }
   return wasmBinding!.batch_verify_tickets(ringSize, commitment, ticketsData, vrfInputDataLen);
 }
+
+/**
+ * Batch-verify ring VRF tickets carrying per-ticket aux data.
+ *
+ * Same as `batchVerifyTickets`, but each ticket in `ticketsData` is laid out as
+ * `signature (784 bytes) || vrfInput (vrfInputDataLen bytes) || auxDataLen
+ * (u32 LE) || auxData`, and the aux data is bound into the ring proof.
+ *
+ * If the layout itself is malformed the number of tickets is unknown, so the
+ * response is just the `0x01` status byte.
+ */
+export function batchVerifyTicketsWithAux(
+  ringSize: number,
+  commitment: Uint8Array,
+  ticketsData: Uint8Array,
+  vrfInputDataLen: number
+): Uint8Array {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchVerifyTicketsWithAux(
+      ringSize,
+      commitment,
+      ticketsData,
+      vrfInputDataLen
+    );
+  }
+  return wasmBinding!.batch_verify_tickets_with_aux(
+    ringSize,
+    commitment,
+    ticketsData,
+    vrfInputDataLen
+  );
+}
+
+/*
+ * Async variants.
+ *
+ * With the native binding the work is done on the libuv thread pool, so the
+ * event loop isn't blocked. The WASM binding has no threads, so there the
+ * call is done synchronously and the result is wrapped in a promise.
+ * Inputs and results are the same as for the synchronous counterparts.
+ */
+
+export async function ringCommitmentAsync(keys: Uint8Array): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.ringCommitmentAsync(keys);
+  }
+  return wasmBinding!.ring_commitment(keys);
+}
+
+export async function ringCommitmentWithOffendersAsync(
+  keys: Uint8Array,
+  offenderIndices: Uint8Array
+): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.ringCommitmentWithOffendersAsync(keys, offenderIndices);
+  }
+  return wasmBinding!.ring_commitment_with_offenders(keys, offenderIndices);
+}
+
+export async function ringCommitmentWithEd25519OffendersAsync(
+  keys: Uint8Array,
+  ed25519Keys: Uint8Array,
+  offenders: Uint8Array
+): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.ringCommitmentWithEd25519OffendersAsync(keys, ed25519Keys, offenders);
+  }
+  return wasmBinding!.ring_commitment_with_ed25519_offenders(keys, ed25519Keys, offenders);
+}
+
+export async function updateRingCommitmentAsync(
+  previousKeys: Uint8Array,
+  previousCommitment: Uint8Array,
+  replacements: Uint8Array
+): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.updateRingCommitmentAsync(previousKeys, previousCommitment, replacements);
+  }
+  return wasmBinding!.update_ring_commitment(previousKeys, previousCommitment, replacements);
+}
+
+export async function generateRingVrfAsync(
+  ringKeys: Uint8Array,
+  proverKeyIndex: number,
+  secretSeed: Uint8Array,
+  vrfInputData: Uint8Array,
+  auxData: Uint8Array = new Uint8Array()
+): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.generateRingVrfWithAuxAsync(
+      ringKeys,
+      proverKeyIndex,
+      secretSeed,
+      vrfInputData,
+      auxData
+    );
+  }
+  return wasmBinding!.generate_ring_vrf_with_aux(
+    ringKeys,
+    proverKeyIndex,
+    secretSeed,
+    vrfInputData,
+    auxData
+  );
+}
+
+export async function generateRingVrfForSecretAsync(
+  ringKeys: Uint8Array,
+  secretSeed: Uint8Array,
+  vrfInputData: Uint8Array,
+  auxData: Uint8Array = new Uint8Array()
+): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.generateRingVrfForSecretAsync(ringKeys, secretSeed, vrfInputData, auxData);
+  }
+  return wasmBinding!.generate_ring_vrf_for_secret(ringKeys, secretSeed, vrfInputData, auxData);
+}
+
+export async function batchGenerateRingVrfAsync(
+  ringKeys: Uint8Array,
+  proverKeyIndex: number,
+  secretSeed: Uint8Array,
+  inputsData: Uint8Array,
+  vrfInputDataLen: number,
+  auxData: Uint8Array = new Uint8Array()
+): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchGenerateRingVrfWithAuxAsync(
+      ringKeys,
+      proverKeyIndex,
+      secretSeed,
+      inputsData,
+      vrfInputDataLen,
+      auxData
+    );
+  }
+  return wasmBinding!.batch_generate_ring_vrf_with_aux(
+    ringKeys,
+    proverKeyIndex,
+    secretSeed,
+    inputsData,
+    vrfInputDataLen,
+    auxData
+  );
+}
+
+export async function batchGenerateRingVrfBelowThresholdAsync(
+  ringKeys: Uint8Array,
+  proverKeyIndex: number,
+  secretSeed: Uint8Array,
+  inputsData: Uint8Array,
+  vrfInputDataLen: number,
+  threshold: Uint8Array,
+  auxData: Uint8Array = new Uint8Array()
+): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchGenerateRingVrfBelowThresholdAsync(
+      ringKeys,
+      proverKeyIndex,
+      secretSeed,
+      inputsData,
+      vrfInputDataLen,
+      threshold,
+      auxData
+    );
+  }
+  return wasmBinding!.batch_generate_ring_vrf_below_threshold(
+    ringKeys,
+    proverKeyIndex,
+    secretSeed,
+    inputsData,
+    vrfInputDataLen,
+    threshold,
+    auxData
+  );
+}
+
+export async function batchGenerateRingVrfForValidatorsAsync(
+  ringKeys: Uint8Array,
+  proverKeyIndices: Uint32Array | readonly number[],
+  secretSeedsData: Uint8Array,
+  secretSeedDataLen: number,
+  inputsData: Uint8Array,
+  vrfInputDataLen: number,
+  auxData: Uint8Array = new Uint8Array()
+): Promise<Uint8Array> {
+  assertInitialized();
+  const proverKeyIndicesData = encodeProverKeyIndices(proverKeyIndices);
+  if (nativeBinding) {
+    return nativeBinding.batchGenerateRingVrfForValidatorsWithAuxAsync(
+      ringKeys,
+      proverKeyIndicesData,
+      secretSeedsData,
+      secretSeedDataLen,
+      inputsData,
+      vrfInputDataLen,
+      auxData
+    );
+  }
+  return wasmBinding!.batch_generate_ring_vrf_for_validators_with_aux(
+    ringKeys,
+    proverKeyIndicesData,
+    secretSeedsData,
+    secretSeedDataLen,
+    inputsData,
+    vrfInputDataLen,
+    auxData
+  );
+}
+
+export async function batchVerifyHeaderSealsAsync(headersData: Uint8Array): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchVerifyHeaderSealsAsync(headersData);
+  }
+  return wasmBinding!.batch_verify_header_seals(headersData);
+}
+
+export async function batchIetfVrfVerifyAsync(signaturesData: Uint8Array): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchIetfVrfVerifyAsync(signaturesData);
+  }
+  return wasmBinding!.batch_ietf_vrf_verify(signaturesData);
+}
+
+export async function batchVerifyTicketsAsync(
+  ringSize: number,
+  commitment: Uint8Array,
+  ticketsData: Uint8Array,
+  vrfInputDataLen: number
+): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchVerifyTicketsAsync(
+      ringSize,
+      commitment,
+      ticketsData,
+      vrfInputDataLen
+    );
+  }
+  return wasmBinding!.batch_verify_tickets(ringSize, commitment, ticketsData, vrfInputDataLen);
+}
+
+export async function batchVerifyTicketsWithAuxAsync(
+  ringSize: number,
+  commitment: Uint8Array,
+  ticketsData: Uint8Array,
+  vrfInputDataLen: number
+): Promise<Uint8Array> {
+  assertInitialized();
+  if (nativeBinding) {
+    return nativeBinding.batchVerifyTicketsWithAuxAsync(
+      ringSize,
+      commitment,
+      ticketsData,
+      vrfInputDataLen
+    );
+  }
+  return wasmBinding!.batch_verify_tickets_with_aux(
+    ringSize,
+    commitment,
+    ticketsData,
+    vrfInputDataLen
+  );
+}
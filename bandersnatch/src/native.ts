@@ -1,7 +1,22 @@
 import { createRequire } from 'module';
 
 export interface NativeBinding {
+  initPcsParams: (bytes: Uint8Array) => Uint8Array;
+  serializeRingParams: (ringSize: number, commitment: Uint8Array) => Uint8Array;
+  loadRingParams: (snapshot: Uint8Array) => Uint8Array;
   ringCommitment: (keys: Uint8Array) => Uint8Array;
+  ringCommitmentWithOffenders: (keys: Uint8Array, offenderIndices: Uint8Array) => Uint8Array;
+  ringCommitmentWithEd25519Offenders: (
+    keys: Uint8Array,
+    ed25519Keys: Uint8Array,
+    offenders: Uint8Array
+  ) => Uint8Array;
+  updateRingCommitment: (
+    previousKeys: Uint8Array,
+    previousCommitment: Uint8Array,
+    replacements: Uint8Array
+  ) => Uint8Array;
+  clearRingCache: () => void;
   derivePublicKey: (seed: Uint8Array) => Uint8Array;
   verifyHeaderSeals: (
     signerKey: Uint8Array,
@@ -11,20 +26,56 @@ export interface NativeBinding {
     entropyData: Uint8Array,
     entropyPrefix: Uint8Array
   ) => Uint8Array;
+  batchVerifyHeaderSeals: (headersData: Uint8Array) => Uint8Array;
+  batchIetfVrfVerify: (signaturesData: Uint8Array) => Uint8Array;
   verifySeal: (
     signerKey: Uint8Array,
     sealData: Uint8Array,
     payload: Uint8Array,
     auxData: Uint8Array
   ) => Uint8Array;
+  generateHeaderSeals: (
+    secretSeed: Uint8Array,
+    sealPayload: Uint8Array,
+    unsealedHeader: Uint8Array,
+    entropyPrefix: Uint8Array
+  ) => Uint8Array;
   generateSeal: (secretSeed: Uint8Array, input: Uint8Array, auxData: Uint8Array) => Uint8Array;
+  generatePedersenVrf: (
+    secretSeed: Uint8Array,
+    input: Uint8Array,
+    auxData: Uint8Array
+  ) => Uint8Array;
+  verifyPedersenVrf: (input: Uint8Array, auxData: Uint8Array, signature: Uint8Array) => Uint8Array;
+  pedersenKeyCommitment: (publicKey: Uint8Array, blinding: Uint8Array) => Uint8Array;
   vrfOutputHash: (secretSeed: Uint8Array, input: Uint8Array) => Uint8Array;
+  vrfOutputFromSignature: (signature: Uint8Array) => Uint8Array;
+  vrfOutputFullHashFromSignature: (signature: Uint8Array) => Uint8Array;
+  vrfOutputHashes: (
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Uint8Array;
   generateRingVrf: (
     ringKeys: Uint8Array,
     proverKeyIndex: number,
     secretSeed: Uint8Array,
     vrfInputData: Uint8Array
   ) => Uint8Array;
+  generateRingVrfWithAux: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    vrfInputData: Uint8Array,
+    auxData: Uint8Array
+  ) => Uint8Array;
+  locateProverKeyIndex: (ringKeys: Uint8Array, secretSeed: Uint8Array) => Uint8Array;
+  generateRingVrfForSecret: (
+    ringKeys: Uint8Array,
+    secretSeed: Uint8Array,
+    vrfInputData: Uint8Array,
+    auxData: Uint8Array
+  ) => Uint8Array;
   batchGenerateRingVrf: (
     ringKeys: Uint8Array,
     proverKeyIndex: number,
@@ -32,6 +83,58 @@ export interface NativeBinding {
     inputsData: Uint8Array,
     vrfInputDataLen: number
   ) => Uint8Array;
+  batchGenerateRingVrfWithAux: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    auxData: Uint8Array
+  ) => Uint8Array;
+  batchGenerateRingVrfBelowThreshold: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    threshold: Uint8Array,
+    auxData: Uint8Array
+  ) => Uint8Array;
+  importSecretSeed: (seed: Uint8Array) => Uint8Array;
+  importSecretScalar: (scalar: Uint8Array) => Uint8Array;
+  exportSecretScalar: (key: number) => Uint8Array;
+  secretKeyPublic: (key: number) => Uint8Array;
+  releaseSecretKey: (key: number) => Uint8Array;
+  importKeystore: (file: Uint8Array, passphrase: Uint8Array) => Uint8Array;
+  exportKeystore: (keys: Uint8Array, passphrase: Uint8Array) => Uint8Array;
+  generateHeaderSealsWithKey: (
+    key: number,
+    sealPayload: Uint8Array,
+    unsealedHeader: Uint8Array,
+    entropyPrefix: Uint8Array
+  ) => Uint8Array;
+  generateSealWithKey: (key: number, input: Uint8Array, auxData: Uint8Array) => Uint8Array;
+  vrfOutputHashWithKey: (key: number, input: Uint8Array) => Uint8Array;
+  generatePedersenVrfWithKey: (
+    key: number,
+    input: Uint8Array,
+    auxData: Uint8Array
+  ) => Uint8Array;
+  generateRingVrfWithKey: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    key: number,
+    vrfInputData: Uint8Array,
+    auxData: Uint8Array
+  ) => Uint8Array;
+  batchGenerateRingVrfWithKey: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    key: number,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    auxData: Uint8Array
+  ) => Uint8Array;
   batchGenerateRingVrfForValidators: (
     ringKeys: Uint8Array,
     proverKeyIndices: Uint8Array,
@@ -40,12 +143,116 @@ export interface NativeBinding {
     inputsData: Uint8Array,
     vrfInputDataLen: number
   ) => Uint8Array;
+  batchGenerateRingVrfForValidatorsWithAux: (
+    ringKeys: Uint8Array,
+    proverKeyIndices: Uint8Array,
+    secretSeedsData: Uint8Array,
+    secretSeedDataLen: number,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    auxData: Uint8Array
+  ) => Uint8Array;
   batchVerifyTickets: (
     ringSize: number,
     commitment: Uint8Array,
     ticketsData: Uint8Array,
     vrfInputDataLen: number
   ) => Uint8Array;
+  batchVerifyTicketsWithAux: (
+    ringSize: number,
+    commitment: Uint8Array,
+    ticketsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Uint8Array;
+  ringCommitmentAsync: (keys: Uint8Array) => Promise<Uint8Array>;
+  ringCommitmentWithOffendersAsync: (
+    keys: Uint8Array,
+    offenderIndices: Uint8Array
+  ) => Promise<Uint8Array>;
+  ringCommitmentWithEd25519OffendersAsync: (
+    keys: Uint8Array,
+    ed25519Keys: Uint8Array,
+    offenders: Uint8Array
+  ) => Promise<Uint8Array>;
+  updateRingCommitmentAsync: (
+    previousKeys: Uint8Array,
+    previousCommitment: Uint8Array,
+    replacements: Uint8Array
+  ) => Promise<Uint8Array>;
+  generateRingVrfAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    vrfInputData: Uint8Array
+  ) => Promise<Uint8Array>;
+  generateRingVrfWithAuxAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    vrfInputData: Uint8Array,
+    auxData: Uint8Array
+  ) => Promise<Uint8Array>;
+  generateRingVrfForSecretAsync: (
+    ringKeys: Uint8Array,
+    secretSeed: Uint8Array,
+    vrfInputData: Uint8Array,
+    auxData: Uint8Array
+  ) => Promise<Uint8Array>;
+  batchGenerateRingVrfAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Promise<Uint8Array>;
+  batchGenerateRingVrfWithAuxAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    auxData: Uint8Array
+  ) => Promise<Uint8Array>;
+  batchGenerateRingVrfBelowThresholdAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndex: number,
+    secretSeed: Uint8Array,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    threshold: Uint8Array,
+    auxData: Uint8Array
+  ) => Promise<Uint8Array>;
+  batchGenerateRingVrfForValidatorsAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndices: Uint8Array,
+    secretSeedsData: Uint8Array,
+    secretSeedDataLen: number,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Promise<Uint8Array>;
+  batchGenerateRingVrfForValidatorsWithAuxAsync: (
+    ringKeys: Uint8Array,
+    proverKeyIndices: Uint8Array,
+    secretSeedsData: Uint8Array,
+    secretSeedDataLen: number,
+    inputsData: Uint8Array,
+    vrfInputDataLen: number,
+    auxData: Uint8Array
+  ) => Promise<Uint8Array>;
+  batchVerifyHeaderSealsAsync: (headersData: Uint8Array) => Promise<Uint8Array>;
+  batchIetfVrfVerifyAsync: (signaturesData: Uint8Array) => Promise<Uint8Array>;
+  batchVerifyTicketsAsync: (
+    ringSize: number,
+    commitment: Uint8Array,
+    ticketsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Promise<Uint8Array>;
+  batchVerifyTicketsWithAuxAsync: (
+    ringSize: number,
+    commitment: Uint8Array,
+    ticketsData: Uint8Array,
+    vrfInputDataLen: number
+  ) => Promise<Uint8Array>;
 }
 
 export async function loadNativeBinding(): Promise<NativeBinding> {
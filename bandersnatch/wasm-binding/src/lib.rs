@@ -1,5 +1,26 @@
+//! WASM bindings of the `bandersnatch-core` FFI.
+//!
+//! Secret arguments are taken as owned `Vec`s (the copies made by the JS glue)
+//! and wiped on drop, instead of being left behind in the WASM heap.
+
 use bandersnatch_core::ffi;
 use wasm_bindgen::prelude::wasm_bindgen;
+use zeroize::Zeroizing;
+
+#[wasm_bindgen]
+pub fn init_pcs_params(bytes: &[u8]) -> Vec<u8> {
+    ffi::init_pcs_params(bytes)
+}
+
+#[wasm_bindgen]
+pub fn serialize_ring_params(ring_size: u32, commitment: &[u8]) -> Vec<u8> {
+    ffi::serialize_ring_params(ring_size, commitment)
+}
+
+#[wasm_bindgen]
+pub fn load_ring_params(snapshot: &[u8]) -> Vec<u8> {
+    ffi::load_ring_params(snapshot)
+}
 
 #[wasm_bindgen]
 pub fn ring_commitment(keys: &[u8]) -> Vec<u8> {
@@ -7,8 +28,37 @@ pub fn ring_commitment(keys: &[u8]) -> Vec<u8> {
 }
 
 #[wasm_bindgen]
-pub fn derive_public_key(seed: &[u8]) -> Vec<u8> {
-    ffi::derive_public_key(seed)
+pub fn ring_commitment_with_offenders(keys: &[u8], offender_indices: &[u8]) -> Vec<u8> {
+    ffi::ring_commitment_with_offenders(keys, offender_indices)
+}
+
+#[wasm_bindgen]
+pub fn ring_commitment_with_ed25519_offenders(
+    keys: &[u8],
+    ed25519_keys: &[u8],
+    offenders: &[u8],
+) -> Vec<u8> {
+    ffi::ring_commitment_with_ed25519_offenders(keys, ed25519_keys, offenders)
+}
+
+#[wasm_bindgen]
+pub fn update_ring_commitment(
+    previous_keys: &[u8],
+    previous_commitment: &[u8],
+    replacements: &[u8],
+) -> Vec<u8> {
+    ffi::update_ring_commitment(previous_keys, previous_commitment, replacements)
+}
+
+#[wasm_bindgen]
+pub fn clear_ring_cache() {
+    ffi::clear_ring_cache()
+}
+
+#[wasm_bindgen]
+pub fn derive_public_key(seed: Vec<u8>) -> Vec<u8> {
+    let seed = Zeroizing::new(seed);
+    ffi::derive_public_key(&seed)
 }
 
 #[wasm_bindgen]
@@ -30,6 +80,16 @@ pub fn verify_header_seals(
     )
 }
 
+#[wasm_bindgen]
+pub fn batch_verify_header_seals(headers_data: &[u8]) -> Vec<u8> {
+    ffi::batch_verify_header_seals(headers_data)
+}
+
+#[wasm_bindgen]
+pub fn batch_ietf_vrf_verify(signatures_data: &[u8]) -> Vec<u8> {
+    ffi::batch_ietf_vrf_verify(signatures_data)
+}
+
 #[wasm_bindgen]
 pub fn verify_seal(
     signer_key: &[u8],
@@ -41,39 +101,263 @@ pub fn verify_seal(
 }
 
 #[wasm_bindgen]
-pub fn generate_seal(secret_seed: &[u8], input: &[u8], aux_data: &[u8]) -> Vec<u8> {
-    ffi::generate_seal(secret_seed, input, aux_data)
+pub fn generate_header_seals(
+    secret_seed: Vec<u8>,
+    seal_payload: &[u8],
+    unsealed_header: &[u8],
+    entropy_prefix: &[u8],
+) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::generate_header_seals(&secret_seed, seal_payload, unsealed_header, entropy_prefix)
+}
+
+#[wasm_bindgen]
+pub fn generate_seal(secret_seed: Vec<u8>, input: &[u8], aux_data: &[u8]) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::generate_seal(&secret_seed, input, aux_data)
+}
+
+#[wasm_bindgen]
+pub fn generate_pedersen_vrf(secret_seed: Vec<u8>, input: &[u8], aux_data: &[u8]) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::generate_pedersen_vrf(&secret_seed, input, aux_data)
+}
+
+#[wasm_bindgen]
+pub fn verify_pedersen_vrf(input: &[u8], aux_data: &[u8], signature: &[u8]) -> Vec<u8> {
+    ffi::verify_pedersen_vrf(input, aux_data, signature)
+}
+
+#[wasm_bindgen]
+pub fn pedersen_key_commitment(public_key: &[u8], blinding: &[u8]) -> Vec<u8> {
+    ffi::pedersen_key_commitment(public_key, blinding)
 }
 
 #[wasm_bindgen]
-pub fn vrf_output_hash(secret_seed: &[u8], input: &[u8]) -> Vec<u8> {
-    ffi::vrf_output_hash(secret_seed, input)
+pub fn vrf_output_hash(secret_seed: Vec<u8>, input: &[u8]) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::vrf_output_hash(&secret_seed, input)
 }
 
 #[wasm_bindgen]
 pub fn generate_ring_vrf(
     ring_keys: &[u8],
     prover_key_index: u32,
-    secret_seed: &[u8],
+    secret_seed: Vec<u8>,
     vrf_input_data: &[u8],
 ) -> Vec<u8> {
-    ffi::generate_ring_vrf(ring_keys, prover_key_index, secret_seed, vrf_input_data)
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::generate_ring_vrf(ring_keys, prover_key_index, &secret_seed, vrf_input_data)
+}
+
+#[wasm_bindgen]
+pub fn generate_ring_vrf_with_aux(
+    ring_keys: &[u8],
+    prover_key_index: u32,
+    secret_seed: Vec<u8>,
+    vrf_input_data: &[u8],
+    aux_data: &[u8],
+) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::generate_ring_vrf_with_aux(
+        ring_keys,
+        prover_key_index,
+        &secret_seed,
+        vrf_input_data,
+        aux_data,
+    )
+}
+
+#[wasm_bindgen]
+pub fn locate_prover_key_index(ring_keys: &[u8], secret_seed: Vec<u8>) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::locate_prover_key_index(ring_keys, &secret_seed)
+}
+
+#[wasm_bindgen]
+pub fn generate_ring_vrf_for_secret(
+    ring_keys: &[u8],
+    secret_seed: Vec<u8>,
+    vrf_input_data: &[u8],
+    aux_data: &[u8],
+) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::generate_ring_vrf_for_secret(ring_keys, &secret_seed, vrf_input_data, aux_data)
 }
 
 #[wasm_bindgen]
 pub fn batch_generate_ring_vrf(
     ring_keys: &[u8],
     prover_key_index: u32,
-    secret_seed: &[u8],
+    secret_seed: Vec<u8>,
     inputs_data: &[u8],
     vrf_input_data_len: u32,
 ) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
     ffi::batch_generate_ring_vrf(
         ring_keys,
         prover_key_index,
-        secret_seed,
+        &secret_seed,
+        inputs_data,
+        vrf_input_data_len,
+    )
+}
+
+#[wasm_bindgen]
+pub fn batch_generate_ring_vrf_with_aux(
+    ring_keys: &[u8],
+    prover_key_index: u32,
+    secret_seed: Vec<u8>,
+    inputs_data: &[u8],
+    vrf_input_data_len: u32,
+    aux_data: &[u8],
+) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::batch_generate_ring_vrf_with_aux(
+        ring_keys,
+        prover_key_index,
+        &secret_seed,
+        inputs_data,
+        vrf_input_data_len,
+        aux_data,
+    )
+}
+
+#[wasm_bindgen]
+pub fn vrf_output_from_signature(signature: &[u8]) -> Vec<u8> {
+    ffi::vrf_output_from_signature(signature)
+}
+
+#[wasm_bindgen]
+pub fn vrf_output_full_hash_from_signature(signature: &[u8]) -> Vec<u8> {
+    ffi::vrf_output_full_hash_from_signature(signature)
+}
+
+#[wasm_bindgen]
+pub fn vrf_output_hashes(
+    secret_seed: Vec<u8>,
+    inputs_data: &[u8],
+    vrf_input_data_len: u32,
+) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::vrf_output_hashes(&secret_seed, inputs_data, vrf_input_data_len)
+}
+
+#[wasm_bindgen]
+pub fn batch_generate_ring_vrf_below_threshold(
+    ring_keys: &[u8],
+    prover_key_index: u32,
+    secret_seed: Vec<u8>,
+    inputs_data: &[u8],
+    vrf_input_data_len: u32,
+    threshold: &[u8],
+    aux_data: &[u8],
+) -> Vec<u8> {
+    let secret_seed = Zeroizing::new(secret_seed);
+    ffi::batch_generate_ring_vrf_below_threshold(
+        ring_keys,
+        prover_key_index,
+        &secret_seed,
         inputs_data,
         vrf_input_data_len,
+        threshold,
+        aux_data,
+    )
+}
+
+#[wasm_bindgen]
+pub fn import_secret_seed(seed: Vec<u8>) -> Vec<u8> {
+    let seed = Zeroizing::new(seed);
+    ffi::import_secret_seed(&seed)
+}
+
+#[wasm_bindgen]
+pub fn import_secret_scalar(scalar: Vec<u8>) -> Vec<u8> {
+    let scalar = Zeroizing::new(scalar);
+    ffi::import_secret_scalar(&scalar)
+}
+
+#[wasm_bindgen]
+pub fn export_secret_scalar(key: u32) -> js_sys::Uint8Array {
+    // Copied straight into JS memory, so that no copy remains in the WASM heap.
+    let result = Zeroizing::new(ffi::export_secret_scalar(key));
+    js_sys::Uint8Array::from(&result[..])
+}
+
+#[wasm_bindgen]
+pub fn secret_key_public(key: u32) -> Vec<u8> {
+    ffi::secret_key_public(key)
+}
+
+#[wasm_bindgen]
+pub fn release_secret_key(key: u32) -> Vec<u8> {
+    ffi::release_secret_key(key)
+}
+
+#[wasm_bindgen]
+pub fn import_keystore(file: &[u8], passphrase: Vec<u8>) -> Vec<u8> {
+    let passphrase = Zeroizing::new(passphrase);
+    ffi::import_keystore(file, &passphrase)
+}
+
+#[wasm_bindgen]
+pub fn export_keystore(keys: &[u8], passphrase: Vec<u8>) -> Vec<u8> {
+    let passphrase = Zeroizing::new(passphrase);
+    ffi::export_keystore(keys, &passphrase)
+}
+
+#[wasm_bindgen]
+pub fn generate_header_seals_with_key(
+    key: u32,
+    seal_payload: &[u8],
+    unsealed_header: &[u8],
+    entropy_prefix: &[u8],
+) -> Vec<u8> {
+    ffi::generate_header_seals_with_key(key, seal_payload, unsealed_header, entropy_prefix)
+}
+
+#[wasm_bindgen]
+pub fn generate_seal_with_key(key: u32, input: &[u8], aux_data: &[u8]) -> Vec<u8> {
+    ffi::generate_seal_with_key(key, input, aux_data)
+}
+
+#[wasm_bindgen]
+pub fn vrf_output_hash_with_key(key: u32, input: &[u8]) -> Vec<u8> {
+    ffi::vrf_output_hash_with_key(key, input)
+}
+
+#[wasm_bindgen]
+pub fn generate_pedersen_vrf_with_key(key: u32, input: &[u8], aux_data: &[u8]) -> Vec<u8> {
+    ffi::generate_pedersen_vrf_with_key(key, input, aux_data)
+}
+
+#[wasm_bindgen]
+pub fn generate_ring_vrf_with_key(
+    ring_keys: &[u8],
+    prover_key_index: u32,
+    key: u32,
+    vrf_input_data: &[u8],
+    aux_data: &[u8],
+) -> Vec<u8> {
+    ffi::generate_ring_vrf_with_key(ring_keys, prover_key_index, key, vrf_input_data, aux_data)
+}
+
+#[wasm_bindgen]
+pub fn batch_generate_ring_vrf_with_key(
+    ring_keys: &[u8],
+    prover_key_index: u32,
+    key: u32,
+    inputs_data: &[u8],
+    vrf_input_data_len: u32,
+    aux_data: &[u8],
+) -> Vec<u8> {
+    ffi::batch_generate_ring_vrf_with_key(
+        ring_keys,
+        prover_key_index,
+        key,
+        inputs_data,
+        vrf_input_data_len,
+        aux_data,
     )
 }
 
@@ -81,21 +365,44 @@ pub fn batch_generate_ring_vrf(
 pub fn batch_generate_ring_vrf_for_validators(
     ring_keys: &[u8],
     prover_key_indices: &[u8],
-    secret_seeds_data: &[u8],
+    secret_seeds_data: Vec<u8>,
     secret_seed_data_len: u32,
     inputs_data: &[u8],
     vrf_input_data_len: u32,
 ) -> Vec<u8> {
+    let secret_seeds_data = Zeroizing::new(secret_seeds_data);
     ffi::batch_generate_ring_vrf_for_validators(
         ring_keys,
         prover_key_indices,
-        secret_seeds_data,
+        &secret_seeds_data,
         secret_seed_data_len,
         inputs_data,
         vrf_input_data_len,
     )
 }
 
+#[wasm_bindgen]
+pub fn batch_generate_ring_vrf_for_validators_with_aux(
+    ring_keys: &[u8],
+    prover_key_indices: &[u8],
+    secret_seeds_data: Vec<u8>,
+    secret_seed_data_len: u32,
+    inputs_data: &[u8],
+    vrf_input_data_len: u32,
+    aux_data: &[u8],
+) -> Vec<u8> {
+    let secret_seeds_data = Zeroizing::new(secret_seeds_data);
+    ffi::batch_generate_ring_vrf_for_validators_with_aux(
+        ring_keys,
+        prover_key_indices,
+        &secret_seeds_data,
+        secret_seed_data_len,
+        inputs_data,
+        vrf_input_data_len,
+        aux_data,
+    )
+}
+
 #[wasm_bindgen]
 pub fn batch_verify_tickets(
     ring_size: u32,
@@ -105,3 +412,13 @@ pub fn batch_verify_tickets(
 ) -> Vec<u8> {
     ffi::batch_verify_tickets(ring_size, commitment, tickets_data, vrf_input_data_len)
 }
+
+#[wasm_bindgen]
+pub fn batch_verify_tickets_with_aux(
+    ring_size: u32,
+    commitment: &[u8],
+    tickets_data: &[u8],
+    vrf_input_data_len: u32,
+) -> Vec<u8> {
+    ffi::batch_verify_tickets_with_aux(ring_size, commitment, tickets_data, vrf_input_data_len)
+}
@@ -0,0 +1,123 @@
+//! Ed25519 signing keys held in Rust memory.
+//!
+//! Seeds are imported once (directly or from a passphrase-encrypted keystore
+//! file) and then addressed by opaque key ids; only key ids, public keys and
+//! signatures are returned to JS. Keys are zeroized when released, and so are
+//! the copies of seeds and passphrases passed in by the JS glue.
+
+use std::sync::{LazyLock, Mutex};
+
+use ed25519_consensus::SigningKey;
+use keystore::{KdfParams, KeyId, KeyRegistry, KeyType, Keystore};
+use wasm_bindgen::prelude::wasm_bindgen;
+use zeroize::Zeroizing;
+
+use crate::{Error, KEY_LENGTH};
+
+const RESULT_OK: u8 = 0;
+const RESULT_ERR: u8 = 1;
+
+static SIGNING_KEYS: LazyLock<Mutex<KeyRegistry<Zeroizing<SigningKey>>>> =
+    LazyLock::new(|| Mutex::new(KeyRegistry::new()));
+
+/// Run `f` with the key registry locked.
+///
+/// The registry is consistent even if the lock was poisoned.
+fn with_registry<R>(f: impl FnOnce(&mut KeyRegistry<Zeroizing<SigningKey>>) -> R) -> R {
+    let mut registry = SIGNING_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut registry)
+}
+
+fn signing_key(seed: &[u8]) -> Result<Zeroizing<SigningKey>, Error> {
+    Ok(Zeroizing::new(SigningKey::try_from(seed)?))
+}
+
+fn status<T: AsRef<[u8]>>(result: Result<T, Error>) -> Vec<u8> {
+    match result {
+        Ok(data) => [&[RESULT_OK], data.as_ref()].concat(),
+        Err(_) => vec![RESULT_ERR],
+    }
+}
+
+/// Import a key from its 32-byte seed. Returns `status || key id (u32 LE)`.
+#[wasm_bindgen]
+pub fn import_ed25519_seed(seed: Vec<u8>) -> Vec<u8> {
+    let seed = Zeroizing::new(seed);
+    status(signing_key(&seed).map(|key| with_registry(|r| r.insert(key)).to_le_bytes()))
+}
+
+/// Public key of an imported key. Returns `status || public key (32 bytes)`.
+#[wasm_bindgen]
+pub fn ed25519_public_key(key: KeyId) -> Vec<u8> {
+    status(
+        with_registry(|r| r.get(key))
+            .map(|key| key.verification_key().to_bytes())
+            .ok_or(Error::UnknownKey),
+    )
+}
+
+/// Sign a message with an imported key. Returns `status || signature (64 bytes)`.
+#[wasm_bindgen]
+pub fn sign_ed25519(key: KeyId, message: &[u8]) -> Vec<u8> {
+    status(
+        with_registry(|r| r.get(key))
+            .map(|key| key.sign(message).to_bytes())
+            .ok_or(Error::UnknownKey),
+    )
+}
+
+/// Forget an imported key. Returns `false` if the key id is unknown.
+#[wasm_bindgen]
+pub fn release_ed25519_key(key: KeyId) -> bool {
+    with_registry(|r| r.remove(key))
+}
+
+/// Import the ed25519 keys of a keystore file. Keys of other types are ignored.
+///
+/// Returns `status || (key id (u32 LE) || public key (32 bytes))*`.
+#[wasm_bindgen]
+pub fn import_ed25519_keystore(file: &[u8], passphrase: Vec<u8>) -> Vec<u8> {
+    let passphrase = Zeroizing::new(passphrase);
+    status(import_keystore(file, &passphrase))
+}
+
+fn import_keystore(file: &[u8], passphrase: &[u8]) -> Result<Vec<u8>, Error> {
+    let keystore = Keystore::decrypt(file, passphrase)?;
+    let keys = keystore
+        .secrets(KeyType::Ed25519)
+        .map(signing_key)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut result = Vec::with_capacity(keys.len() * (4 + KEY_LENGTH));
+    for key in keys {
+        let public_key = key.verification_key().to_bytes();
+        result.extend_from_slice(&with_registry(|r| r.insert(key)).to_le_bytes());
+        result.extend_from_slice(&public_key);
+    }
+    Ok(result)
+}
+
+/// Seal keys (concatenated u32 LE key ids) into a keystore file encrypted
+/// with the passphrase. Returns `status || keystore file`.
+#[wasm_bindgen]
+pub fn export_ed25519_keystore(keys: &[u8], passphrase: Vec<u8>) -> Vec<u8> {
+    let passphrase = Zeroizing::new(passphrase);
+    status(export_keystore(keys, &passphrase, KdfParams::default()))
+}
+
+pub(crate) fn export_keystore(
+    keys: &[u8],
+    passphrase: &[u8],
+    params: KdfParams,
+) -> Result<Vec<u8>, Error> {
+    if !keys.len().is_multiple_of(4) {
+        return Err(Error::UnknownKey);
+    }
+    let mut keystore = Keystore::new();
+    for id in keys.chunks_exact(4) {
+        let id = KeyId::from_le_bytes([id[0], id[1], id[2], id[3]]);
+        let key = with_registry(|r| r.get(id)).ok_or(Error::UnknownKey)?;
+        keystore.add(KeyType::Ed25519, key.as_bytes())?;
+    }
+    Ok(keystore.encrypt(passphrase, params)?)
+}
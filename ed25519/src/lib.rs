@@ -2,6 +2,7 @@ use ed25519_consensus::{Error as Ed25519Error, Signature, VerificationKey};
 use std::io::{self, Cursor, Read};
 use wasm_bindgen::prelude::wasm_bindgen;
 
+pub mod keys;
 mod test;
 
 const KEY_LENGTH: usize = 32;
@@ -77,9 +78,12 @@ fn read_chunk(cursor: &mut Cursor<&[u8]>) -> Result<(VerificationKey, Signature,
     ))
 }
 
+#[derive(Debug)]
 pub enum Error {
     Io(io::Error),
     Crypto(Ed25519Error),
+    Keystore(keystore::Error),
+    UnknownKey,
 }
 
 impl From<io::Error> for Error {
@@ -93,3 +97,9 @@ impl From<Ed25519Error> for Error {
         Self::Crypto(value)
     }
 }
+
+impl From<keystore::Error> for Error {
+    fn from(value: keystore::Error) -> Self {
+        Self::Keystore(value)
+    }
+}
@@ -15,7 +15,7 @@ fn main() {
     let message_len = message.len() as u8;
     println!("Message len: 0x{}", &message_len);
     let data = &[key, signature, vec![message_len], message].concat();
-    println!("Data: 0x{}", hex::encode(&data));
+    println!("Data: 0x{}", hex::encode(data));
     let verify_ed25519_result = verify_ed25519(&data.clone());
     println!("verify_ed25519 result: {:?}", verify_ed25519_result);
     let verify_ed25519_batch_result = verify_ed25519_batch(&data.clone());
@@ -19,6 +19,47 @@ mod tests {
         ;
 
         let result = verify_ed25519_batch(&data.clone());
-        assert_eq!(result, true);
+        assert!(result);
+    }
+
+    #[test]
+    fn should_sign_with_keys_from_keystore() {
+        use crate::keys::{
+            ed25519_public_key, export_keystore, import_ed25519_keystore, import_ed25519_seed,
+            release_ed25519_key, sign_ed25519,
+        };
+        use ed25519_consensus::{Signature, VerificationKey};
+        use keystore::KdfParams;
+
+        let seed = [5u8; 32];
+        let imported = import_ed25519_seed(seed.to_vec());
+        assert_eq!(imported[0], 0);
+        let key = u32::from_le_bytes(imported[1..5].try_into().unwrap());
+        let public_key = ed25519_public_key(key)[1..].to_vec();
+
+        let params = KdfParams {
+            log_n: 4,
+            ..Default::default()
+        };
+        let file = export_keystore(&key.to_le_bytes(), b"passphrase", params).unwrap();
+        assert!(!file.windows(32).any(|w| w == seed));
+        assert!(release_ed25519_key(key));
+        assert_eq!(sign_ed25519(key, b"message"), vec![1]);
+
+        let imported = import_ed25519_keystore(&file, b"passphrase".to_vec());
+        assert_eq!(imported.len(), 1 + 4 + 32);
+        assert_eq!(imported[0], 0);
+        assert_eq!(imported[5..], public_key[..]);
+        let key = u32::from_le_bytes(imported[1..5].try_into().unwrap());
+
+        let signature = sign_ed25519(key, b"message");
+        assert_eq!(signature[0], 0);
+        let verification_key = VerificationKey::try_from(&public_key[..]).unwrap();
+        let signature = Signature::try_from(&signature[1..]).unwrap();
+        assert!(verification_key.verify(&signature, b"message").is_ok());
+
+        assert_eq!(import_ed25519_keystore(&file, b"wrong".to_vec()), vec![1]);
+        assert_eq!(import_ed25519_seed(vec![1u8; 31]), vec![1]);
+        assert!(release_ed25519_key(key));
     }
 }
@@ -0,0 +1,264 @@
+//! Passphrase-encrypted storage of validator secret keys.
+//!
+//! Secrets are meant to be loaded into Rust memory once, kept there behind
+//! opaque [`KeyId`]s (see [`KeyRegistry`]) and zeroized when dropped, so that
+//! only key ids and public keys have to cross the FFI boundary.
+//!
+//! At rest, a [`Keystore`] is sealed with XChaCha20-Poly1305 under a key
+//! derived from a passphrase with scrypt. File layout (integers little endian):
+//!
+//! ```text
+//! magic "TBKS" (4) || version (1) || scrypt log_n (1) || scrypt r (4) || scrypt p (4)
+//!     || salt (32) || nonce (24) || ciphertext
+//! ```
+//!
+//! The header is authenticated as associated data. The plaintext is a sequence
+//! of `key type (1) || secret length (2) || secret` entries.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use zeroize::Zeroizing;
+
+mod registry;
+mod test;
+
+pub use registry::{KeyId, KeyRegistry};
+
+const MAGIC: &[u8; 4] = b"TBKS";
+const VERSION: u8 = 1;
+const SALT_SIZE: usize = 32;
+const NONCE_SIZE: usize = 24;
+const HEADER_SIZE: usize = MAGIC.len() + 1 + 1 + 4 + 4 + SALT_SIZE + NONCE_SIZE;
+
+/// Largest accepted scrypt cost.
+pub const MAX_LOG_N: u8 = 20;
+
+/// Largest accepted scrypt block size.
+pub const MAX_R: u32 = 32;
+
+/// Largest accepted scrypt parallelization.
+pub const MAX_P: u32 = 16;
+
+/// Largest accepted total scrypt cost `128 * r * 2^log_n * p` (1 GiB), so that
+/// a crafted file can't exhaust memory or time.
+pub const MAX_KDF_COST: u64 = 1 << 30;
+
+/// Kind of a stored secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    /// Bandersnatch secret scalar (32 bytes, little endian).
+    Bandersnatch,
+    /// Ed25519 seed (32 bytes).
+    Ed25519,
+}
+
+impl KeyType {
+    fn to_byte(self) -> u8 {
+        match self {
+            KeyType::Bandersnatch => 1,
+            KeyType::Ed25519 => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(KeyType::Bandersnatch),
+            2 => Some(KeyType::Ed25519),
+            _ => None,
+        }
+    }
+}
+
+/// Keystore errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Not a keystore file, or a truncated one.
+    InvalidFormat,
+    /// Keystore file of an unknown version.
+    UnsupportedVersion,
+    /// Invalid or too expensive scrypt parameters.
+    InvalidKdfParams,
+    /// Wrong passphrase or tampered file.
+    DecryptionFailed,
+    /// Secret is too long to be stored.
+    SecretTooLong,
+    /// No randomness available for the salt and nonce.
+    RandomnessUnavailable,
+}
+
+/// scrypt parameters of the passphrase key derivation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Base 2 logarithm of the CPU/memory cost.
+    pub log_n: u8,
+    /// Block size.
+    pub r: u32,
+    /// Parallelization.
+    pub p: u32,
+}
+
+impl Default for KdfParams {
+    /// 32 MiB of memory, which is still fine in browsers and WASM.
+    fn default() -> Self {
+        Self {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    fn derive_key(&self, passphrase: &[u8], salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, Error> {
+        if self.log_n > MAX_LOG_N || self.r > MAX_R || self.p > MAX_P {
+            return Err(Error::InvalidKdfParams);
+        }
+        let cost = 128 * u64::from(self.r) * (1u64 << self.log_n) * u64::from(self.p);
+        if cost > MAX_KDF_COST {
+            return Err(Error::InvalidKdfParams);
+        }
+        let params = scrypt::Params::new(self.log_n, self.r, self.p, 32)
+            .map_err(|_| Error::InvalidKdfParams)?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        scrypt::scrypt(passphrase, salt, &params, &mut key[..])
+            .map_err(|_| Error::InvalidKdfParams)?;
+        Ok(key)
+    }
+}
+
+/// A secret held by the keystore, zeroized on drop.
+pub struct StoredKey {
+    key_type: KeyType,
+    secret: Zeroizing<Vec<u8>>,
+}
+
+impl StoredKey {
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    pub fn secret(&self) -> &[u8] {
+        &self.secret
+    }
+}
+
+/// Collection of secrets that can be sealed with a passphrase.
+#[derive(Default)]
+pub struct Keystore {
+    keys: Vec<StoredKey>,
+}
+
+impl Keystore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a copy of the secret.
+    pub fn add(&mut self, key_type: KeyType, secret: &[u8]) -> Result<(), Error> {
+        if secret.len() > u16::MAX as usize {
+            return Err(Error::SecretTooLong);
+        }
+        self.keys.push(StoredKey {
+            key_type,
+            secret: Zeroizing::new(secret.to_vec()),
+        });
+        Ok(())
+    }
+
+    /// All stored keys, in insertion order.
+    pub fn keys(&self) -> &[StoredKey] {
+        &self.keys
+    }
+
+    /// Secrets of given type, in insertion order.
+    pub fn secrets(&self, key_type: KeyType) -> impl Iterator<Item = &[u8]> {
+        self.keys
+            .iter()
+            .filter(move |key| key.key_type == key_type)
+            .map(StoredKey::secret)
+    }
+
+    /// Seal the keystore with a passphrase.
+    pub fn encrypt(&self, passphrase: &[u8], params: KdfParams) -> Result<Vec<u8>, Error> {
+        let mut salt = [0u8; SALT_SIZE];
+        let mut nonce = [0u8; NONCE_SIZE];
+        getrandom::getrandom(&mut salt).map_err(|_| Error::RandomnessUnavailable)?;
+        getrandom::getrandom(&mut nonce).map_err(|_| Error::RandomnessUnavailable)?;
+        let key = params.derive_key(passphrase, &salt)?;
+
+        let mut file = Vec::with_capacity(HEADER_SIZE);
+        file.extend_from_slice(MAGIC);
+        file.push(VERSION);
+        file.push(params.log_n);
+        file.extend_from_slice(&params.r.to_le_bytes());
+        file.extend_from_slice(&params.p.to_le_bytes());
+        file.extend_from_slice(&salt);
+        file.extend_from_slice(&nonce);
+
+        let plaintext_len = self.keys.iter().map(|k| 3 + k.secret.len()).sum();
+        let mut plaintext = Zeroizing::new(Vec::with_capacity(plaintext_len));
+        for key in &self.keys {
+            plaintext.push(key.key_type.to_byte());
+            plaintext.extend_from_slice(&(key.secret.len() as u16).to_le_bytes());
+            plaintext.extend_from_slice(&key.secret);
+        }
+
+        let cipher = XChaCha20Poly1305::new((&*key).into());
+        let payload = Payload {
+            msg: &plaintext,
+            aad: &file,
+        };
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), payload)
+            .expect("encryption of an in-memory buffer can't fail");
+        file.extend(ciphertext);
+        Ok(file)
+    }
+
+    /// Open a keystore sealed with [`Keystore::encrypt`].
+    ///
+    /// Entries of unknown key types are skipped.
+    pub fn decrypt(file: &[u8], passphrase: &[u8]) -> Result<Self, Error> {
+        if file.len() < HEADER_SIZE || &file[..MAGIC.len()] != MAGIC {
+            return Err(Error::InvalidFormat);
+        }
+        let (header, ciphertext) = file.split_at(HEADER_SIZE);
+        if header[4] != VERSION {
+            return Err(Error::UnsupportedVersion);
+        }
+        let params = KdfParams {
+            log_n: header[5],
+            r: u32::from_le_bytes(header[6..10].try_into().expect("slice of length 4")),
+            p: u32::from_le_bytes(header[10..14].try_into().expect("slice of length 4")),
+        };
+        let salt = &header[14..14 + SALT_SIZE];
+        let nonce = &header[14 + SALT_SIZE..];
+        let key = params.derive_key(passphrase, salt)?;
+
+        let cipher = XChaCha20Poly1305::new((&*key).into());
+        let payload = Payload {
+            msg: ciphertext,
+            aad: header,
+        };
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(XNonce::from_slice(nonce), payload)
+                .map_err(|_| Error::DecryptionFailed)?,
+        );
+
+        let mut keystore = Keystore::new();
+        let mut rest = &plaintext[..];
+        while !rest.is_empty() {
+            if rest.len() < 3 {
+                return Err(Error::InvalidFormat);
+            }
+            let secret_len = u16::from_le_bytes([rest[1], rest[2]]) as usize;
+            let secret = rest.get(3..3 + secret_len).ok_or(Error::InvalidFormat)?;
+            if let Some(key_type) = KeyType::from_byte(rest[0]) {
+                keystore.add(key_type, secret)?;
+            }
+            rest = &rest[3 + secret_len..];
+        }
+        Ok(keystore)
+    }
+}
@@ -0,0 +1,48 @@
+//! In-memory registry of secret keys addressed by opaque ids.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Opaque id of a key held in a [`KeyRegistry`].
+pub type KeyId = u32;
+
+/// Secret keys addressed by [`KeyId`]s.
+///
+/// Keys are shared as `Arc`s, so callers can sign without holding the
+/// registry lock. Zeroizing the key material on drop is up to `T`.
+pub struct KeyRegistry<T> {
+    next_id: KeyId,
+    keys: HashMap<KeyId, Arc<T>>,
+}
+
+impl<T> Default for KeyRegistry<T> {
+    fn default() -> Self {
+        Self {
+            next_id: 1,
+            keys: HashMap::new(),
+        }
+    }
+}
+
+impl<T> KeyRegistry<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a key under a fresh id. Id `0` is never used.
+    pub fn insert(&mut self, key: T) -> KeyId {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.keys.insert(id, Arc::new(key));
+        id
+    }
+
+    pub fn get(&self, id: KeyId) -> Option<Arc<T>> {
+        self.keys.get(&id).cloned()
+    }
+
+    /// Forget a key. Returns `false` if the id was unknown.
+    pub fn remove(&mut self, id: KeyId) -> bool {
+        self.keys.remove(&id).is_some()
+    }
+}
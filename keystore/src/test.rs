@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    use crate::{Error, KdfParams, KeyRegistry, KeyType, Keystore, MAX_LOG_N, MAX_P, MAX_R};
+
+    /// Cheap parameters, so the tests don't spend their time in scrypt.
+    const TEST_PARAMS: KdfParams = KdfParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    fn test_keystore() -> Keystore {
+        let mut keystore = Keystore::new();
+        keystore.add(KeyType::Bandersnatch, &[1u8; 32]).unwrap();
+        keystore.add(KeyType::Ed25519, &[2u8; 32]).unwrap();
+        keystore.add(KeyType::Bandersnatch, &[3u8; 32]).unwrap();
+        keystore
+    }
+
+    #[test]
+    fn should_encrypt_and_decrypt_keystore() {
+        let file = test_keystore().encrypt(b"passphrase", TEST_PARAMS).unwrap();
+
+        let keystore = Keystore::decrypt(&file, b"passphrase").unwrap();
+        assert_eq!(keystore.keys().len(), 3);
+        let bandersnatch: Vec<_> = keystore.secrets(KeyType::Bandersnatch).collect();
+        assert_eq!(bandersnatch, vec![&[1u8; 32][..], &[3u8; 32][..]]);
+        let ed25519: Vec<_> = keystore.secrets(KeyType::Ed25519).collect();
+        assert_eq!(ed25519, vec![&[2u8; 32][..]]);
+    }
+
+    #[test]
+    fn should_not_leak_secrets_into_keystore_file() {
+        let file = test_keystore().encrypt(b"passphrase", TEST_PARAMS).unwrap();
+        for secret in [[1u8; 32], [2u8; 32], [3u8; 32]] {
+            assert!(!file.windows(32).any(|w| w == secret));
+        }
+
+        // Random salt and nonce.
+        let other = test_keystore().encrypt(b"passphrase", TEST_PARAMS).unwrap();
+        assert_ne!(file, other);
+    }
+
+    #[test]
+    fn should_reject_wrong_passphrase_or_tampered_keystore() {
+        let file = test_keystore().encrypt(b"passphrase", TEST_PARAMS).unwrap();
+        assert!(matches!(
+            Keystore::decrypt(&file, b"wrong"),
+            Err(Error::DecryptionFailed)
+        ));
+
+        for index in [5, 20, file.len() - 1] {
+            let mut tampered = file.clone();
+            tampered[index] ^= 1;
+            assert!(Keystore::decrypt(&tampered, b"passphrase").is_err());
+        }
+
+        assert!(matches!(
+            Keystore::decrypt(&file[..40], b"passphrase"),
+            Err(Error::InvalidFormat)
+        ));
+        let mut other_version = file.clone();
+        other_version[4] = 2;
+        assert!(matches!(
+            Keystore::decrypt(&other_version, b"passphrase"),
+            Err(Error::UnsupportedVersion)
+        ));
+        let mut expensive = file.clone();
+        expensive[5] = MAX_LOG_N + 1;
+        assert!(matches!(
+            Keystore::decrypt(&expensive, b"passphrase"),
+            Err(Error::InvalidKdfParams)
+        ));
+    }
+
+    #[test]
+    fn should_reject_too_expensive_block_size_or_parallelization() {
+        let file = test_keystore().encrypt(b"passphrase", TEST_PARAMS).unwrap();
+        // r and p are the u32 LE fields after magic, version and log_n.
+        for (offset, value) in [
+            (6, u32::MAX),
+            (6, MAX_R + 1),
+            (10, u32::MAX),
+            (10, MAX_P + 1),
+        ] {
+            let mut expensive = file.clone();
+            expensive[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            assert!(matches!(
+                Keystore::decrypt(&expensive, b"passphrase"),
+                Err(Error::InvalidKdfParams)
+            ));
+        }
+
+        for params in [
+            KdfParams {
+                r: MAX_R + 1,
+                ..TEST_PARAMS
+            },
+            // Each parameter within its bound, but too expensive together.
+            KdfParams {
+                log_n: MAX_LOG_N,
+                r: MAX_R,
+                p: MAX_P,
+            },
+        ] {
+            assert!(matches!(
+                test_keystore().encrypt(b"passphrase", params),
+                Err(Error::InvalidKdfParams)
+            ));
+        }
+    }
+
+    #[test]
+    fn should_allocate_and_release_key_ids() {
+        let mut registry = KeyRegistry::new();
+        let first = registry.insert("first");
+        let second = registry.insert("second");
+        assert_ne!(first, 0);
+        assert_ne!(first, second);
+        assert_eq!(registry.get(first).as_deref(), Some(&"first"));
+
+        assert!(registry.remove(first));
+        assert!(!registry.remove(first));
+        assert!(registry.get(first).is_none());
+        assert_eq!(registry.get(second).as_deref(), Some(&"second"));
+    }
+}
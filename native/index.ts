@@ -1,13 +1,22 @@
 export * as bandersnatch from "@typeberry/bandersnatch";
 export * as ed25519 from "@typeberry/ed25519";
-export * as reedSolomon from "@typeberry/reed-solomon";
 
 import bandersnatchInit from "@typeberry/bandersnatch";
 import bandersnatchWasm from "../bandersnatch/wasm-binding/pkg/bandersnatch_wasm_bg.wasm";
 import ed25519Init from "@typeberry/ed25519";
 import ed25519Wasm from "../ed25519/pkg/ed25519_wasm_bg.wasm";
-import reedSolomonInit from "@typeberry/reed-solomon";
+import * as reedSolomonSimd from "@typeberry/reed-solomon";
 import reedSolomonWasm from "../reed-solomon/pkg/reed_solomon_wasm_bg.wasm";
+import * as reedSolomonScalar from "../reed-solomon/pkg-scalar/reed_solomon_wasm.js";
+import reedSolomonScalarWasm from "../reed-solomon/pkg-scalar/reed_solomon_wasm_bg.wasm";
+
+/**
+ * Reed-Solomon bindings.
+ *
+ * `init.reedSolomon()` switches them to the scalar build (with its own JS glue)
+ * for runtimes lacking WASM SIMD, so they must be accessed after init.
+ */
+export let reedSolomon: typeof reedSolomonSimd = reedSolomonSimd;
 
 
 export async function initAll() {
@@ -26,8 +35,33 @@ function initOnce<T>(doInit: () => Promise<T>) {
   };
 }
 
+/**
+ * Check if the runtime supports WASM fixed-width SIMD (`simd128`).
+ *
+ * Validates a minimal module using a `v128` instruction.
+ */
+function hasWasmSimd(): boolean {
+  try {
+    return WebAssembly.validate(
+      new Uint8Array([
+        0, 97, 115, 109, 1, 0, 0, 0, 1, 5, 1, 96, 0, 1, 123, 3, 2, 1, 0, 10, 10, 1, 8, 0, 65, 0,
+        253, 15, 253, 98, 11,
+      ])
+    );
+  } catch {
+    return false;
+  }
+}
+
 export const init = {
   bandersnatch: initOnce(async () => await bandersnatchInit({ module_or_path: await bandersnatchWasm() })),
   ed25519: initOnce(async () => await ed25519Init({ module_or_path: await ed25519Wasm() })),
-  reedSolomon: initOnce(async () => await reedSolomonInit({ module_or_path: await reedSolomonWasm() })),
+  // The SIMD build is preferred, with a fallback to the scalar one for runtimes lacking WASM SIMD.
+  reedSolomon: initOnce(async () => {
+    if (hasWasmSimd()) {
+      return await reedSolomonSimd.default({ module_or_path: await reedSolomonWasm() });
+    }
+    reedSolomon = reedSolomonScalar;
+    return await reedSolomonScalar.default({ module_or_path: await reedSolomonScalarWasm() });
+  }),
 };
@@ -15,6 +15,11 @@ use web_sys::js_sys;
 /// that resides at `[ idx * shard_len .. idx * shard_len + shard_len )`
 /// in `data` array.
 ///
+/// Shards may be longer than 64KiB (`shard_len` is `u32`), so big
+/// bundle-level shards don't need to be chopped on the JS side.
+/// Indices stay 16-bit, since the total number of original and recovery
+/// shards is limited to 65536 by the underlying implementation.
+///
 /// This collection is only used to get the data from JS or pass the data back.
 /// Internally we convert it to [`RsShardsCollection`], which copies
 /// the memory to/from WASM.
@@ -23,7 +28,7 @@ pub struct ShardsCollection {
     /// Number of shards within the collection.
     pub length: u32,
     /// The length of each shard.
-    pub shard_len: u16,
+    pub shard_len: u32,
     /// All shards concatenated.
     data: js_sys::Uint8Array,
     /// Optional indices for shards in the collection.
@@ -34,11 +39,11 @@ pub struct ShardsCollection {
 impl ShardsCollection {
     #[wasm_bindgen(constructor)]
     pub fn new(
-        shard_len: u16,
+        shard_len: u32,
         data: js_sys::Uint8Array,
         indices: Option<js_sys::Uint16Array>,
     ) -> Self {
-        let length = data.length() / shard_len as u32;
+        let length = data.length() / shard_len;
         if let Some(ref indices) = indices {
             assert!(
                 indices.length() == length,
@@ -99,7 +104,7 @@ impl ShardsCollection {
 /// A Rust equivalent of [`ShardsCollection`].
 struct RsShardsCollection {
     pub length: usize,
-    pub shard_len: u16,
+    pub shard_len: u32,
     pub data: Vec<u8>,
     pub indices: Option<Vec<u16>>,
 }
@@ -172,7 +177,7 @@ fn rs_encode(
     shards: RsShardsCollection,
 ) -> Result<RsShardsCollection, Error> {
     let mut encoder =
-        ReedSolomonEncoder::new(shards.length, recovery_count, shards.shard_len.into())?;
+        ReedSolomonEncoder::new(shards.length, recovery_count, shards.shard_len as usize)?;
 
     for i in 0..shards.length {
         assert!(
@@ -206,7 +211,7 @@ fn rs_decode(
     shards: RsShardsCollection,
 ) -> Result<RsShardsCollection, Error> {
     let mut decoder =
-        ReedSolomonDecoder::new(original_count, recovery_count, shards.shard_len.into())?;
+        ReedSolomonDecoder::new(original_count, recovery_count, shards.shard_len as usize)?;
 
     for i in 0..shards.length {
         let idx = shards.chunk_index_at(i) as usize;
@@ -258,6 +263,150 @@ pub fn decode(
     Ok(result.into())
 }
 
+/// Incremental Reed-Solomon encoder.
+///
+/// Instead of materializing all original shards in one big `Uint8Array`,
+/// the JS side can feed them in chunks (each chunk being any number of
+/// whole shards) and collect the recovery shards at the end.
+/// The JS side doesn't need to hold the whole payload at once, but the
+/// encoder still buffers every original shard in WASM memory until `finish`.
+#[wasm_bindgen]
+pub struct StreamingEncoder {
+    encoder: ReedSolomonEncoder,
+    original_count: usize,
+    recovery_count: usize,
+    shard_len: u32,
+    /// Number of original shards added so far.
+    added: usize,
+}
+
+#[wasm_bindgen]
+impl StreamingEncoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        original_count: u16,
+        recovery_count: u16,
+        shard_len: u32,
+    ) -> Result<StreamingEncoder, String> {
+        let encoder = ReedSolomonEncoder::new(
+            original_count as usize,
+            recovery_count as usize,
+            shard_len as usize,
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            encoder,
+            original_count: original_count as usize,
+            recovery_count: recovery_count as usize,
+            shard_len,
+            added: 0,
+        })
+    }
+
+    /// Number of original shards added so far.
+    #[wasm_bindgen(getter)]
+    pub fn added(&self) -> u32 {
+        self.added as u32
+    }
+
+    /// Add the next original shards.
+    ///
+    /// `data` must contain a whole number of shards (`shard_len` each),
+    /// which are added in order following any previously added shards.
+    /// On error none of the shards in `data` are added.
+    #[wasm_bindgen]
+    pub fn add_original_shards(&mut self, data: &[u8]) -> Result<(), String> {
+        let shard_len = self.shard_len as usize;
+        if shard_len == 0 || !data.len().is_multiple_of(shard_len) {
+            return Err(format!(
+                "Data length {} is not a multiple of shard length {}.",
+                data.len(),
+                shard_len
+            ));
+        }
+        let count = data.len() / shard_len;
+        if count > self.original_count - self.added {
+            return Err(format!(
+                "Adding {} shards to the {} already added exceeds the original count {}.",
+                count, self.added, self.original_count
+            ));
+        }
+
+        for shard in data.chunks_exact(shard_len) {
+            self.encoder
+                .add_original_shard(shard)
+                .map_err(|e| e.to_string())?;
+            self.added += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Produce the recovery shards.
+    ///
+    /// All `original_count` shards must have been added before calling this.
+    /// NOTE this object is destroyed after encoding.
+    #[wasm_bindgen]
+    pub fn finish(self) -> Result<ShardsCollection, String> {
+        let result = self.finish_rs().map_err(|e| e.to_string())?;
+        Ok(result.into())
+    }
+}
+
+impl StreamingEncoder {
+    fn finish_rs(mut self) -> Result<RsShardsCollection, Error> {
+        let result = self.encoder.encode()?;
+
+        let mut data = Vec::with_capacity(self.recovery_count * self.shard_len as usize);
+        let mut indices = Vec::with_capacity(self.recovery_count);
+        for (idx, chunk) in result.recovery_iter().enumerate() {
+            indices.push((self.original_count + idx) as u16);
+            data.extend(chunk);
+        }
+
+        Ok(RsShardsCollection {
+            length: self.recovery_count,
+            shard_len: self.shard_len,
+            data,
+            indices: Some(indices),
+        })
+    }
+}
+
+/// Information about the Reed-Solomon build loaded into this module.
+///
+/// The JS side may use that to verify that the SIMD build was actually loaded
+/// (or that it fell back to the scalar one). Only build-time facts are
+/// reported: the engine itself is picked by the underlying library at runtime.
+#[wasm_bindgen]
+pub struct Capabilities {
+    /// Whether the module was compiled with WASM SIMD (`simd128`) enabled.
+    pub simd: bool,
+    variant: &'static str,
+}
+
+#[wasm_bindgen]
+impl Capabilities {
+    /// Build variant: `simd` (built with the `simd` feature) or `scalar`.
+    #[wasm_bindgen(getter)]
+    pub fn variant(&self) -> String {
+        self.variant.to_string()
+    }
+}
+
+#[wasm_bindgen]
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        simd: cfg!(target_feature = "simd128"),
+        variant: if cfg!(feature = "simd") {
+            "simd"
+        } else {
+            "scalar"
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -271,7 +420,7 @@ mod tests {
 
         let shards = RsShardsCollection {
             length: 3,
-            shard_len: SHARD as u16,
+            shard_len: SHARD as u32,
             data,
             indices: None,
         };
@@ -285,7 +434,7 @@ mod tests {
         let encoded = test_data(recovery_count);
 
         assert_eq!(encoded.length, recovery_count);
-        assert_eq!(encoded.shard_len, SHARD as u16);
+        assert_eq!(encoded.shard_len, SHARD as u32);
         assert_eq!(encoded.chunk_index_at(0), 3);
         assert_eq!(encoded.chunk_index_at(1), 4);
         assert_eq!(encoded.chunk_index_at(2), 5);
@@ -365,4 +514,110 @@ mod tests {
         assert_eq!(decoded.chunk_at(0), &[1u8; SHARD]);
         assert_eq!(decoded.chunk_at(1), &[3u8; SHARD]);
     }
+
+    #[test]
+    fn should_encode_and_decode_shards_longer_than_u16() {
+        const BIG_SHARD: usize = 128 * 1024;
+        let mut data = vec![];
+        data.extend(vec![1u8; BIG_SHARD]);
+        data.extend(vec![2u8; BIG_SHARD]);
+        data.extend(vec![3u8; BIG_SHARD]);
+
+        let shards = RsShardsCollection {
+            length: 3,
+            shard_len: BIG_SHARD as u32,
+            data,
+            indices: None,
+        };
+        let encoded = rs_encode(3, shards).unwrap();
+        assert_eq!(encoded.shard_len, BIG_SHARD as u32);
+        assert_eq!(encoded.data.len(), 3 * BIG_SHARD);
+
+        let to_decode = RsShardsCollection {
+            length: 3,
+            shard_len: encoded.shard_len,
+            data: {
+                let mut data = vec![];
+                data.extend(encoded.chunk_at(0));
+                data.extend(encoded.chunk_at(1));
+                data.extend(vec![3u8; BIG_SHARD]);
+                data
+            },
+            indices: vec![encoded.chunk_index_at(0), encoded.chunk_index_at(1), 2].into(),
+        };
+
+        let decoded = rs_decode(3, 3, to_decode).unwrap();
+
+        assert_eq!(decoded.length, 2);
+        assert_eq!(decoded.chunk_index_at(0), 0u16);
+        assert_eq!(decoded.chunk_index_at(1), 1u16);
+        assert_eq!(decoded.chunk_at(0), vec![1u8; BIG_SHARD].as_slice());
+        assert_eq!(decoded.chunk_at(1), vec![2u8; BIG_SHARD].as_slice());
+    }
+
+    #[test]
+    fn should_stream_encode_shards_in_chunks() {
+        let recovery_count = 5;
+        let expected = test_data(recovery_count);
+
+        let mut encoder = StreamingEncoder::new(3, recovery_count as u16, SHARD as u32).unwrap();
+        let mut first = vec![];
+        first.extend([1u8; SHARD]);
+        first.extend([2u8; SHARD]);
+        encoder.add_original_shards(&first).unwrap();
+        encoder.add_original_shards(&[3u8; SHARD]).unwrap();
+        assert_eq!(encoder.added(), 3);
+
+        let encoded = encoder.finish_rs().unwrap();
+
+        assert_eq!(encoded.length, expected.length);
+        assert_eq!(encoded.shard_len, expected.shard_len);
+        assert_eq!(encoded.indices, expected.indices);
+        assert_eq!(encoded.data, expected.data);
+    }
+
+    #[test]
+    fn should_reject_partial_shards_when_streaming() {
+        let mut encoder = StreamingEncoder::new(3, 2, SHARD as u32).unwrap();
+
+        assert!(encoder.add_original_shards(&[1u8; SHARD + 1]).is_err());
+        assert_eq!(encoder.added(), 0);
+    }
+
+    #[test]
+    fn should_not_add_any_shard_of_a_rejected_chunk() {
+        let expected = test_data(2);
+        let mut encoder = StreamingEncoder::new(3, 2, SHARD as u32).unwrap();
+        encoder.add_original_shards(&[1u8; SHARD]).unwrap();
+
+        assert!(encoder.add_original_shards(&[2u8; 3 * SHARD]).is_err());
+        assert_eq!(encoder.added(), 1);
+
+        let mut rest = vec![];
+        rest.extend([2u8; SHARD]);
+        rest.extend([3u8; SHARD]);
+        encoder.add_original_shards(&rest).unwrap();
+        assert_eq!(encoder.finish_rs().unwrap().data, expected.data);
+    }
+
+    #[test]
+    fn should_fail_streaming_with_missing_shards() {
+        let mut encoder = StreamingEncoder::new(3, 2, SHARD as u32).unwrap();
+        encoder.add_original_shards(&[1u8; SHARD]).unwrap();
+
+        assert!(encoder.finish_rs().is_err());
+    }
+
+    #[test]
+    fn should_report_capabilities() {
+        let caps = capabilities();
+
+        assert_eq!(caps.simd, cfg!(target_feature = "simd128"));
+        let variant = if cfg!(feature = "simd") {
+            "simd"
+        } else {
+            "scalar"
+        };
+        assert_eq!(caps.variant(), variant);
+    }
 }